@@ -1,14 +1,45 @@
 use crate::{
-    ui::{self, IconCacheInterface},
-    window_manager::{DisplayInfo, WindowManager},
+    borderless_gaming, bundle,
+    cli::Command,
+    config::{Config, ConflictPolicy, Favorite, ImportReport},
+    hotkeys::{Hotkey, HotkeyAction, HotkeyService},
+    instance, ipc,
+    launcher::{self, ExitAction},
+    logging,
+    overlay::Outline,
+    picker,
+    tray::Tray,
+    ui::{
+        self, DisplayMove, HistoryAction, HotkeyEdit, IconCacheInterface, ProfileAction,
+        ProfileTransfer, SelectorAction, StatusAction, StatusMessage, TransferStatus,
+        WindowListView,
+    },
+    window_manager::{self, DisplayInfo, FrameMode, WindowInfo, WindowManager},
 };
 use eframe::egui;
 use std::{
     collections::HashMap,
-    sync::mpsc::Receiver,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+        mpsc::Receiver,
+    },
     time::{Duration, Instant},
 };
 
+static QUITTING: AtomicBool = AtomicBool::new(false);
+
+pub fn quit(ctx: &egui::Context)
+{
+    QUITTING.store(true, Ordering::SeqCst);
+    // A hidden window gets no WM_PAINT, so eframe never runs another frame to act on the
+    // Close command. Show it first so handle_close_request still runs the shutdown path.
+    instance::show_main_window(ctx);
+    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+    ctx.request_repaint();
+}
+
 struct IconCache
 {
     cache: HashMap<String, (egui::TextureHandle, Instant)>,
@@ -86,6 +117,7 @@ impl IconCacheInterface for IconCache
 pub struct BorderlessApp
 {
     window_manager: WindowManager,
+    config: Arc<Mutex<Config>>,
     selected_window: Option<usize>,
     last_refresh: std::time::Instant,
     icon_cache: IconCache,
@@ -94,27 +126,73 @@ pub struct BorderlessApp
     displays: Vec<DisplayInfo>,
     needs_repaint: bool,
     refresh_receiver: Option<Receiver<Vec<crate::window_manager::WindowInfo>>>,
+    pending_command: Option<Command>,
+    _tray: Option<Tray>,
+    hotkeys: HotkeyService,
+    rebinding: Option<HotkeyAction>,
+    pick_receiver: Option<Receiver<Option<isize>>>,
+    highlight: Option<Outline>,
+    highlighted_window: Option<isize>,
+    preview: Option<(Outline, Instant)>,
+    new_exclude: String,
+    transfer: ProfileTransfer,
+    new_size: String,
+    status: Option<StatusMessage>,
 }
 
 impl BorderlessApp
 {
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self
+    pub fn new(cc: &eframe::CreationContext<'_>, command: Command, config: Config) -> Self
     {
         ui::setup_dark_theme(&cc.egui_ctx);
 
         let window_manager = WindowManager::new();
         let displays = window_manager.get_displays();
 
+        let resize_to_screen = config.resize_to_screen;
+        let selected_display = config
+            .display
+            .as_ref()
+            .and_then(|name| displays.iter().position(|display| &display.name == name))
+            .or(if !displays.is_empty() { Some(0) } else { None });
+
+        window_manager.set_filters(config.filters.clone());
+        window_manager.set_window_size(config.window_size);
+
+        let config = Arc::new(Mutex::new(config));
+
+        ipc::start_server(window_manager.clone(), Arc::clone(&config), cc.egui_ctx.clone());
+
+        let tray = Tray::start(window_manager.clone(), Arc::clone(&config), cc.egui_ctx.clone())
+            .map_err(|e| logging::error("tray", format!("Failed to create tray icon: {}", e)))
+            .ok();
+
+        let hotkeys =
+            HotkeyService::start(window_manager.clone(), Arc::clone(&config), cc.egui_ctx.clone());
+
         let mut app = Self {
             window_manager,
+            config,
             selected_window: None,
             last_refresh: std::time::Instant::now(),
             icon_cache: IconCache::new(),
-            resize_to_screen: true,
-            selected_display: if !displays.is_empty() { Some(0) } else { None },
+            resize_to_screen,
+            selected_display,
             displays,
             needs_repaint: false,
             refresh_receiver: None,
+            pending_command: Some(command),
+            _tray: tray,
+            hotkeys,
+            rebinding: None,
+            pick_receiver: None,
+            highlight: None,
+            highlighted_window: None,
+            preview: None,
+            new_exclude: String::new(),
+            transfer: ProfileTransfer { path: bundle::default_path(), ..Default::default() },
+            new_size: String::new(),
+            status: None,
         };
 
         app.start_async_refresh();
@@ -130,7 +208,7 @@ impl BorderlessApp
         }
     }
 
-    fn handle_refresh(&mut self)
+    fn handle_refresh(&mut self, ctx: &egui::Context)
     {
         if let Some(receiver) = &self.refresh_receiver {
             if let Ok(windows) = receiver.try_recv() {
@@ -140,10 +218,12 @@ impl BorderlessApp
                     self.needs_repaint = true;
 
                     if let Some(selected) = self.selected_window {
-                        if selected >= self.window_manager.get_windows().len() {
+                        if selected >= self.window_manager.window_count() {
                             self.selected_window = None;
                         }
                     }
+
+                    self.run_pending_command(ctx);
                 }
                 self.refresh_receiver = None;
             }
@@ -155,8 +235,169 @@ impl BorderlessApp
         }
     }
 
+    fn run_pending_command(&mut self, ctx: &egui::Context)
+    {
+        match self.pending_command.take() {
+            Some(Command::ApplyProfile(name)) => self.apply_profile_by_name(&name),
+            Some(Command::Run(options)) => {
+                if let Err(e) =
+                    launcher::run(&options, self.window_manager.clone(), &self.config, ctx.clone())
+                {
+                    logging::error("launcher", e.to_string());
+                }
+            },
+            Some(Command::ImportBorderlessGaming(path)) => {
+                self.import_borderless_gaming(path.as_deref().map(Path::new));
+            },
+            Some(Command::ExportProfiles { path, names }) => {
+                self.export_profiles(Path::new(&path), &names);
+            },
+            Some(Command::ImportProfiles { path, on_conflict }) => {
+                self.import_profiles(Path::new(&path), on_conflict);
+            },
+            Some(Command::Relaunch { select, .. }) => {
+                let windows = self.window_manager.get_windows();
+                self.selected_window =
+                    select.and_then(|hwnd| windows.iter().position(|window| window.hwnd == hwnd));
+            },
+            Some(Command::Show) | None => {},
+        }
+    }
+
+    fn apply_profile_by_name(&mut self, name: &str)
+    {
+        let profile = self.config.lock().unwrap().find_profile(name).cloned();
+        let Some(profile) = profile else {
+            self.show_status(format!("Unknown profile '{}'", name), true);
+            return;
+        };
+
+        match self.window_manager.apply_profile_to_matching(&profile, &self.displays) {
+            Ok(targets) if !targets.is_empty() => {
+                self.show_status(
+                    format!("Applied profile '{}' to {} window(s)", name, targets.len()),
+                    false,
+                );
+                self.refresh_receiver = None;
+                self.start_async_refresh();
+            },
+            Ok(_) => self.show_status(format!("No window matches profile '{}'", name), false),
+            Err(e) => self.show_status(format!("Failed to apply profile '{}': {}", name, e), true),
+        }
+    }
+
+    fn import_borderless_gaming(&mut self, path: Option<&Path>)
+    {
+        let mut config = self.config.lock().unwrap();
+        let policy = config.import_conflict;
+        let result = borderless_gaming::import(path, &mut config, &self.displays, policy);
+        drop(config);
+
+        self.finish_import(result);
+    }
+
+    fn import_profiles(&mut self, path: &Path, policy: Option<ConflictPolicy>)
+    {
+        let mut config = self.config.lock().unwrap();
+        let policy = policy.unwrap_or(config.import_conflict);
+        let result = bundle::import(path, &mut config, &self.displays, policy);
+        drop(config);
+
+        self.finish_import(result);
+    }
+
+    fn finish_import(&mut self, result: anyhow::Result<ImportReport>)
+    {
+        if result.is_ok()
+            && let Err(e) = self.config.lock().unwrap().save()
+        {
+            logging::error("config", format!("Failed to save config: {}", e));
+        }
+
+        self.transfer.status = Some(match result {
+            Ok(report) => TransferStatus::Imported(report),
+            Err(e) => TransferStatus::Failed(e.to_string()),
+        });
+        self.needs_repaint = true;
+    }
+
+    fn export_profiles(&mut self, path: &Path, names: &[String])
+    {
+        let profiles = self.config.lock().unwrap().profiles_named(names);
+        let result = profiles.and_then(|profiles| {
+            bundle::export(path, &profiles, &self.displays).map(|()| profiles.len())
+        });
+
+        self.transfer.status = Some(match result {
+            Ok(count) => TransferStatus::Exported { path: path.display().to_string(), count },
+            Err(e) => TransferStatus::Failed(e.to_string()),
+        });
+        self.needs_repaint = true;
+    }
+
+    fn handle_profile_action(&mut self, action: ProfileAction)
+    {
+        let path = PathBuf::from(self.transfer.path.trim());
+
+        match action {
+            ProfileAction::Export => {
+                let names: Vec<String> = self.transfer.selected.iter().cloned().collect();
+                self.export_profiles(&path, &names);
+            },
+            ProfileAction::Import => self.import_profiles(&path, None),
+            ProfileAction::ImportBorderlessGaming => self.import_borderless_gaming(None),
+            ProfileAction::SetConflict(policy) => {
+                self.update_config(|config| config.import_conflict = policy);
+            },
+        }
+    }
+
+    fn handle_dropped_files(&mut self, ctx: &egui::Context)
+    {
+        let dropped: Vec<PathBuf> = ctx.input(|input| {
+            input.raw.dropped_files.iter().filter_map(|file| file.path.clone()).collect()
+        });
+
+        for path in dropped {
+            if bundle::is_bundle_path(&path) {
+                self.import_profiles(&path, None);
+            } else {
+                self.transfer.status = Some(TransferStatus::Failed(format!(
+                    "'{}' is not a .json or .toml profile file",
+                    path.display()
+                )));
+            }
+        }
+    }
+
+    fn update_config(&self, update: impl FnOnce(&mut Config))
+    {
+        let mut config = self.config.lock().unwrap();
+        update(&mut config);
+
+        if let Err(e) = config.save() {
+            logging::error("config", format!("Failed to save config: {}", e));
+        }
+    }
+
+    fn handle_close_request(&mut self, ctx: &egui::Context)
+    {
+        if ctx.input(|i| i.viewport().close_requested())
+            && !QUITTING.load(Ordering::SeqCst)
+            && self.config.lock().unwrap().close_to_tray
+        {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            instance::hide_main_window();
+        }
+    }
+
     fn handle_keyboard_input(&mut self, ctx: &egui::Context)
     {
+        if self.rebinding.is_some() {
+            self.capture_hotkey(ctx);
+            return;
+        }
+
         if ctx.input(|i| i.key_pressed(egui::Key::F5)) {
             self.refresh_receiver = None;
             self.start_async_refresh();
@@ -167,29 +408,359 @@ impl BorderlessApp
             self.selected_window = None;
             self.needs_repaint = true;
         }
+
+        if !ctx.wants_keyboard_input() {
+            if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::Z)) {
+                self.handle_history_action(HistoryAction::Undo(1));
+            }
+            if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::Y)) {
+                self.handle_history_action(HistoryAction::Redo(1));
+            }
+        }
+    }
+
+    fn handle_history_action(&mut self, action: HistoryAction)
+    {
+        let (count, redo) = match action {
+            HistoryAction::Undo(count) => (count, false),
+            HistoryAction::Redo(count) => (count, true),
+        };
+
+        let verb = if redo { "Redid" } else { "Undid" };
+        for _ in 0..count {
+            let result = if redo { self.window_manager.redo() } else { self.window_manager.undo() };
+            match result {
+                Ok(Some(label)) => self.show_status(format!("{} '{}'", verb, label), false),
+                Ok(None) => break,
+                Err(e) => {
+                    let verb = if redo { "redo" } else { "undo" };
+                    self.show_status(format!("Failed to {}: {}", verb, e), true);
+                    break;
+                },
+            }
+        }
+
+        self.preview = None;
+        self.refresh_receiver = None;
+        self.start_async_refresh();
+        self.needs_repaint = true;
+    }
+
+    fn capture_hotkey(&mut self, ctx: &egui::Context)
+    {
+        let Some(action) = self.rebinding else {
+            return;
+        };
+
+        let pressed = ctx.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Key { key, pressed: true, modifiers, .. } => Some((*key, *modifiers)),
+                _ => None,
+            })
+        });
+
+        let Some((key, modifiers)) = pressed else {
+            return;
+        };
+
+        if key == egui::Key::Escape && modifiers.is_none() {
+            self.rebinding = None;
+            self.hotkeys.reload();
+            return;
+        }
+
+        let hotkey = Hotkey::from_egui(modifiers, key).to_string();
+        if Hotkey::parse(&hotkey).is_err() {
+            return;
+        }
+
+        self.rebinding = None;
+        self.update_config(|config| {
+            config.hotkeys.insert(action, hotkey);
+        });
+        self.hotkeys.reload();
+    }
+
+    fn handle_hotkey_edit(&mut self, edit: HotkeyEdit)
+    {
+        match edit {
+            HotkeyEdit::Rebind(action) => {
+                self.rebinding = Some(action);
+                self.hotkeys.suspend();
+            },
+            HotkeyEdit::Clear(action) => {
+                self.rebinding = None;
+                self.update_config(|config| {
+                    config.hotkeys.insert(action, String::new());
+                });
+                self.hotkeys.reload();
+            },
+        }
+    }
+
+    fn handle_pick_result(&mut self)
+    {
+        let Some(receiver) = &self.pick_receiver else {
+            return;
+        };
+
+        let Ok(picked) = receiver.try_recv() else {
+            return;
+        };
+
+        self.pick_receiver = None;
+        self.needs_repaint = true;
+
+        let Some(hwnd) = picked else {
+            return;
+        };
+
+        self.window_manager.refresh_windows();
+        self.last_refresh = std::time::Instant::now();
+
+        let windows = self.window_manager.get_windows();
+        match windows.iter().position(|window| window.hwnd == hwnd) {
+            Some(index) => self.selected_window = Some(index),
+            None => logging::warn("picker", "Picked window is not in the window list"),
+        }
+    }
+
+    fn update_highlight(&mut self, hovered_window: Option<isize>)
+    {
+        if hovered_window == self.highlighted_window {
+            return;
+        }
+
+        self.highlighted_window = hovered_window;
+
+        let Some(rect) = hovered_window.and_then(window_manager::frame_rect) else {
+            if let Some(highlight) = &self.highlight {
+                highlight.hide();
+            }
+            return;
+        };
+
+        if self.highlight.is_none() {
+            self.highlight = Outline::new((255, 176, 64), 4)
+                .map_err(|e| {
+                    logging::error("overlay", format!("Failed to create highlight overlay: {}", e))
+                })
+                .ok();
+        }
+
+        if let Some(highlight) = &self.highlight {
+            highlight.show(rect);
+        }
+    }
+
+    fn toggle_preview(&mut self)
+    {
+        if self.preview.take().is_some() {
+            return;
+        }
+
+        let windows = self.window_manager.get_windows();
+        let Some(window) = self.selected_window.and_then(|index| windows.get(index)) else {
+            return;
+        };
+
+        let (resize_to_screen, display, frame_mode) = self.placement_for(window);
+        let selected_display = if resize_to_screen { display } else { None };
+
+        let rect = match self.window_manager.target_rect(
+            window.hwnd,
+            resize_to_screen,
+            selected_display,
+            frame_mode,
+        ) {
+            Ok(rect) => rect,
+            Err(e) => {
+                logging::warn(
+                    "overlay",
+                    format!(
+                        "Failed to preview window title={}: {}",
+                        logging::title(&window.title),
+                        e
+                    ),
+                );
+                return;
+            },
+        };
+
+        match Outline::ghost((80, 160, 255)) {
+            Ok(ghost) => {
+                ghost.show(rect);
+                self.preview = Some((ghost, Instant::now()));
+            },
+            Err(e) => logging::error("overlay", format!("Failed to create preview overlay: {}", e)),
+        }
+    }
+
+    fn expire_preview(&mut self)
+    {
+        if self.preview.as_ref().is_some_and(|(_, shown)| shown.elapsed() >= Duration::from_secs(5))
+        {
+            self.preview = None;
+        }
+
+        if self.status.as_ref().is_some_and(|status| {
+            !status.is_error && status.shown.elapsed() >= Duration::from_secs(4)
+        }) {
+            self.status = None;
+        }
+    }
+
+    fn show_status(&mut self, text: String, is_error: bool)
+    {
+        self.status =
+            Some(StatusMessage { text, is_error, offer_relaunch: false, shown: Instant::now() });
+        self.needs_repaint = true;
+    }
+
+    fn offer_relaunch(&mut self, window: &WindowInfo) -> bool
+    {
+        if !window.requires_elevation() {
+            return false;
+        }
+
+        self.status = Some(StatusMessage {
+            text: format!(
+                "'{}' runs as administrator, so Windows blocks ihateborders from changing it. Restart ihateborders as administrator to manage it.",
+                window.title
+            ),
+            is_error: true,
+            offer_relaunch: true,
+            shown: Instant::now(),
+        });
+        self.needs_repaint = true;
+        true
+    }
+
+    fn relaunch_elevated(&mut self, ctx: &egui::Context)
+    {
+        let windows = self.window_manager.get_windows();
+        let select =
+            self.selected_window.and_then(|index| windows.get(index)).map(|window| window.hwnd);
+
+        match instance::relaunch_elevated(select) {
+            Ok(()) => quit(ctx),
+            Err(e) => {
+                logging::warn("instance", format!("Relaunch as administrator failed: {}", e));
+                self.show_status(e.to_string(), true);
+            },
+        }
+    }
+
+    fn placement_for(&self, window: &WindowInfo) -> (bool, Option<&DisplayInfo>, FrameMode)
+    {
+        let (favorite, frame_mode) = {
+            let config = self.config.lock().unwrap();
+            (config.favorite_for(window).cloned(), config.frame_mode)
+        };
+
+        match favorite {
+            Some(favorite) => (
+                favorite.resize_to_screen,
+                window_manager::find_display(&self.displays, favorite.display.as_deref()),
+                favorite.frame_mode,
+            ),
+            None => (
+                self.resize_to_screen,
+                self.selected_display.and_then(|idx| self.displays.get(idx)),
+                frame_mode,
+            ),
+        }
+    }
+
+    fn handle_selector_action(&mut self, action: SelectorAction, ctx: &egui::Context)
+    {
+        let windows = self.window_manager.get_windows();
+
+        match action {
+            SelectorAction::ToggleFavorite(index) => {
+                let Some(window) = windows.get(index) else {
+                    return;
+                };
+
+                let display = self
+                    .selected_display
+                    .and_then(|index| self.displays.get(index))
+                    .map(|display| display.name.clone());
+                let frame_mode = self.config.lock().unwrap().frame_mode;
+                let favorite =
+                    Favorite::from_window(window, self.resize_to_screen, display, frame_mode);
+
+                self.update_config(|config| {
+                    match config.favorites.iter().position(|favorite| favorite.matches(window)) {
+                        Some(existing) => {
+                            config.favorites.remove(existing);
+                        },
+                        None => config.favorites.push(favorite),
+                    }
+                });
+            },
+            SelectorAction::RemoveFavorite(index) => {
+                self.update_config(|config| {
+                    if index < config.favorites.len() {
+                        config.favorites.remove(index);
+                    }
+                });
+            },
+            SelectorAction::Launch(index) => {
+                let favorite = self.config.lock().unwrap().favorites.get(index).cloned();
+
+                if let Some(favorite) = favorite
+                    && let Some(exe_path) = &favorite.exe_path
+                    && let Err(e) = launcher::spawn(
+                        exe_path,
+                        &[],
+                        favorite.as_profile(),
+                        ExitAction::None,
+                        self.window_manager.clone(),
+                        ctx.clone(),
+                    )
+                {
+                    logging::error("launcher", e.to_string());
+                }
+            },
+        }
+
+        self.needs_repaint = true;
     }
 
     fn handle_window_action(&mut self, window_index: usize)
     {
+        self.preview = None;
+
         let windows = self.window_manager.get_windows();
         if let Some(window) = windows.get(window_index) {
-            let selected_display = if self.resize_to_screen {
-                self.selected_display.and_then(|idx| self.displays.get(idx))
-            } else {
-                None
-            };
+            if self.offer_relaunch(window) {
+                return;
+            }
 
-            if let Err(e) = self.window_manager.toggle_borderless(
+            let (resize_to_screen, display, frame_mode) = self.placement_for(window);
+            let selected_display = if resize_to_screen { display } else { None };
+            let effects = self.config.lock().unwrap().effects();
+
+            let was_borderless = self.window_manager.is_borderless(window.hwnd);
+            let result = self.window_manager.toggle_borderless(
                 window.hwnd,
-                self.resize_to_screen,
+                resize_to_screen,
                 selected_display,
-            ) {
-                eprintln!("Failed to toggle borderless for window '{}': {}", window.title, e);
-            } else {
-                self.refresh_receiver = None;
-                self.start_async_refresh();
-                self.needs_repaint = true;
+                frame_mode,
+                effects,
+            );
+
+            match result {
+                Ok(()) if was_borderless => {
+                    self.show_status(format!("Restored the borders of '{}'", window.title), false)
+                },
+                Ok(()) => self.show_status(format!("'{}' is now borderless", window.title), false),
+                Err(e) => self.show_status(format!("'{}': {}", window.title, e), true),
             }
+
+            self.refresh_receiver = None;
+            self.start_async_refresh();
         }
     }
 }
@@ -198,33 +769,246 @@ impl eframe::App for BorderlessApp
 {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame)
     {
-        self.handle_refresh();
+        self.handle_refresh(ctx);
+        self.handle_dropped_files(ctx);
+        self.handle_pick_result();
+        self.expire_preview();
         self.handle_keyboard_input(ctx);
+        self.handle_close_request(ctx);
 
         self.icon_cache.cleanup_expired();
 
         egui::CentralPanel::default().show(ctx, |ui| {
-            let windows = self.window_manager.get_windows();
+            egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                let windows = self.window_manager.get_windows();
 
-            ui::render_header(ui, windows.len());
+                ui::render_header(ui, windows.len());
 
-            ui::render_window_selector(
-                ui,
-                windows,
-                &mut self.selected_window,
-                &mut self.icon_cache,
-            );
+                if ui::render_selector_header(ui, self.pick_receiver.is_some()) {
+                    self.pick_receiver = Some(picker::start(ctx.clone()));
+                }
 
-            ui::render_position_checkbox(ui, &mut self.resize_to_screen);
+                let (mut sort, mut group_by_process) = {
+                    let config = self.config.lock().unwrap();
+                    (config.sort, config.group_by_process)
+                };
 
-            if self.resize_to_screen {
-                ui::render_display_selector(ui, &self.displays, &mut self.selected_display);
-            }
+                if ui::render_list_options(ui, &mut sort, &mut group_by_process) {
+                    self.update_config(|config| {
+                        config.sort = sort;
+                        config.group_by_process = group_by_process;
+                    });
+                }
 
-            if let Some(window_index) = ui::render_action_button(ui, windows, self.selected_window)
-            {
-                self.handle_window_action(window_index);
-            }
+                let order = sort.sorted_indices(&windows);
+                let previous_selection = self.selected_window;
+                let mut hovered_window = None;
+
+                let favorites = self.config.lock().unwrap().favorites.clone();
+                let view =
+                    WindowListView { order: &order, group_by_process, favorites: &favorites };
+
+                if let Some(action) = ui::render_window_selector(
+                    ui,
+                    &windows,
+                    &mut self.selected_window,
+                    &mut self.icon_cache,
+                    &mut hovered_window,
+                    &view,
+                ) {
+                    self.handle_selector_action(action, ctx);
+                }
+
+                self.update_highlight(hovered_window);
+
+                if let Some(window) = self.selected_window.and_then(|index| windows.get(index)) {
+                    ui::render_window_details(ui, window);
+                }
+
+                if previous_selection != self.selected_window {
+                    self.preview = None;
+                }
+
+                let previous_placement = (self.resize_to_screen, self.selected_display);
+
+                ui::render_position_checkbox(ui, &mut self.resize_to_screen);
+
+                if self.resize_to_screen {
+                    ui::render_display_selector(ui, &self.displays, &mut self.selected_display);
+
+                    let (mut window_size, mut custom_sizes) = {
+                        let config = self.config.lock().unwrap();
+                        (config.window_size, config.custom_sizes.clone())
+                    };
+                    if ui::render_size_selector(
+                        ui,
+                        &mut window_size,
+                        &mut custom_sizes,
+                        &mut self.new_size,
+                    ) {
+                        self.window_manager.set_window_size(window_size);
+                        self.update_config(|config| {
+                            config.window_size = window_size;
+                            config.custom_sizes = custom_sizes;
+                        });
+                    }
+                }
+
+                if previous_placement != (self.resize_to_screen, self.selected_display) {
+                    let display = self
+                        .selected_display
+                        .and_then(|index| self.displays.get(index))
+                        .map(|display| display.name.clone());
+                    let resize_to_screen = self.resize_to_screen;
+
+                    self.update_config(|config| {
+                        config.resize_to_screen = resize_to_screen;
+                        config.display = display;
+                    });
+                }
+
+                let (mut close_to_tray, mut start_minimized) = {
+                    let config = self.config.lock().unwrap();
+                    (config.close_to_tray, config.start_minimized)
+                };
+
+                let mut frame_mode = self.config.lock().unwrap().frame_mode;
+                if ui::render_frame_mode_selector(ui, &mut frame_mode) {
+                    self.update_config(|config| config.frame_mode = frame_mode);
+                }
+
+                let mut hide_taskbar = self.config.lock().unwrap().hide_taskbar;
+                if ui::render_taskbar_option(ui, &mut hide_taskbar) {
+                    self.update_config(|config| config.hide_taskbar = hide_taskbar);
+                }
+
+                let mut backdrop = self.config.lock().unwrap().backdrop;
+                if ui::render_backdrop_option(ui, &mut backdrop) {
+                    self.update_config(|config| config.backdrop = backdrop);
+                }
+
+                let mut lock_cursor = self.config.lock().unwrap().lock_cursor;
+                if ui::render_cursor_option(ui, &mut lock_cursor) {
+                    self.update_config(|config| config.lock_cursor = lock_cursor);
+                }
+
+                if ui::render_tray_options(ui, &mut close_to_tray, &mut start_minimized) {
+                    self.update_config(|config| {
+                        config.close_to_tray = close_to_tray;
+                        config.start_minimized = start_minimized;
+                    });
+                }
+
+                if let Some(window_index) =
+                    ui::render_action_button(ui, &windows, self.selected_window)
+                {
+                    self.handle_window_action(window_index);
+                }
+
+                match self.status.as_ref().and_then(|status| ui::render_status(ui, status)) {
+                    Some(StatusAction::Dismiss) => self.status = None,
+                    Some(StatusAction::Relaunch) => self.relaunch_elevated(ctx),
+                    None => {},
+                }
+
+                if let Some(window) = self.selected_window.and_then(|index| windows.get(index)) {
+                    let mut modifiers = window.modifiers;
+                    if ui::render_modifier_controls(ui, &mut modifiers) {
+                        if !self.offer_relaunch(window)
+                            && let Err(e) =
+                                self.window_manager.set_modifiers(window.hwnd, modifiers)
+                        {
+                            self.show_status(format!("'{}': {}", window.title, e), true);
+                        }
+                        self.needs_repaint = true;
+                    }
+
+                    if let Some(action) = ui::render_display_controls(ui, &self.displays)
+                        && !self.offer_relaunch(window)
+                    {
+                        let result = match action {
+                            DisplayMove::Send(index) => {
+                                self.window_manager.send_to_display(window.hwnd, index)
+                            },
+                            DisplayMove::Next => {
+                                self.window_manager.move_to_next_display(window.hwnd)
+                            },
+                            DisplayMove::Swap => {
+                                self.window_manager.swap_displays(window.hwnd, None).map(|_| ())
+                            },
+                        };
+                        if let Err(e) = result {
+                            self.show_status(format!("'{}': {}", window.title, e), true);
+                        }
+                        self.needs_repaint = true;
+                    }
+                }
+
+                if ui::render_preview_button(
+                    ui,
+                    self.selected_window.is_some(),
+                    self.preview.is_some(),
+                ) {
+                    self.toggle_preview();
+                }
+
+                let mut favorites = self.config.lock().unwrap().favorites.clone();
+                if ui::render_favorite_settings(ui, &mut favorites, &self.displays) {
+                    self.update_config(|config| config.favorites = favorites);
+                }
+
+                let (profiles, conflict) = {
+                    let config = self.config.lock().unwrap();
+                    (config.profiles.clone(), config.import_conflict)
+                };
+                if let Some(action) =
+                    ui::render_profile_settings(ui, &profiles, &mut self.transfer, conflict)
+                {
+                    self.handle_profile_action(action);
+                }
+
+                let (undo, redo) = self.window_manager.history();
+                if let Some(action) = ui::render_history(ui, &undo, &redo) {
+                    self.handle_history_action(action);
+                }
+
+                let mut filters = self.config.lock().unwrap().filters.clone();
+                if ui::render_filter_settings(ui, &mut filters, &mut self.new_exclude) {
+                    self.window_manager.set_filters(filters.clone());
+                    self.update_config(|config| config.filters = filters);
+                    self.refresh_receiver = None;
+                    self.start_async_refresh();
+                    self.needs_repaint = true;
+                }
+
+                let bindings = self.config.lock().unwrap().hotkeys.clone();
+                if let Some(edit) = ui::render_hotkey_settings(
+                    ui,
+                    &bindings,
+                    &self.hotkeys.statuses(),
+                    self.rebinding,
+                ) {
+                    self.handle_hotkey_edit(edit);
+                }
+
+                let (mut log_level, mut redact_titles) = {
+                    let config = self.config.lock().unwrap();
+                    (config.log_level, config.redact_titles)
+                };
+                if ui::render_log_panel(
+                    ui,
+                    &mut log_level,
+                    &mut redact_titles,
+                    &logging::recent(),
+                    logging::path().as_deref(),
+                ) {
+                    logging::configure(log_level, redact_titles);
+                    self.update_config(|config| {
+                        config.log_level = log_level;
+                        config.redact_titles = redact_titles;
+                    });
+                }
+            });
         });
 
         if self.needs_repaint {
@@ -236,24 +1020,24 @@ impl eframe::App for BorderlessApp
     }
 }
 
-pub fn create_app_options() -> eframe::NativeOptions
+pub fn create_app_options(config: &Config) -> eframe::NativeOptions
 {
     let icon_data = load_icon();
 
     eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_title("ihateborders")
-            .with_inner_size([350.0, 320.0])
-            .with_min_inner_size([350.0, 320.0])
-            .with_max_inner_size([350.0, 320.0])
-            .with_resizable(false)
+            .with_inner_size([350.0, 345.0])
+            .with_min_inner_size([350.0, 345.0])
             .with_maximize_button(false)
+            .with_drag_and_drop(true)
+            .with_visible(!config.start_minimized)
             .with_icon(icon_data),
         ..Default::default()
     }
 }
 
-fn load_icon() -> egui::IconData
+pub fn load_icon() -> egui::IconData
 {
     let icon_bytes = include_bytes!("../assets/icon.ico");
 
@@ -0,0 +1,234 @@
+use crate::{logging, overlay::Backdrop, window_manager};
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+use windows::Win32::{
+    Foundation::{HWND, RECT},
+    Graphics::Gdi::{GetMonitorInfoW, MONITOR_DEFAULTTONEAREST, MONITORINFO, MonitorFromWindow},
+    UI::WindowsAndMessaging::{
+        DispatchMessageW, IsIconic, IsWindow, IsWindowVisible, MSG, PM_REMOVE, PeekMessageW,
+    },
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+static STATE: LazyLock<Mutex<BackdropState>> =
+    LazyLock::new(|| Mutex::new(BackdropState::default()));
+
+#[derive(Default)]
+struct BackdropState
+{
+    targets: HashMap<isize, [u8; 3]>,
+    running: bool,
+}
+
+pub fn show(hwnd: isize, color: [u8; 3])
+{
+    let mut state = STATE.lock().unwrap();
+    state.targets.insert(hwnd, color);
+
+    if !state.running {
+        state.running = true;
+        std::thread::spawn(run);
+    }
+}
+
+pub fn hide(hwnd: isize)
+{
+    STATE.lock().unwrap().targets.remove(&hwnd);
+}
+
+fn uncovered_regions(monitor: RECT, window: RECT) -> Vec<RECT>
+{
+    let covered = RECT {
+        left: window.left.max(monitor.left),
+        top: window.top.max(monitor.top),
+        right: window.right.min(monitor.right),
+        bottom: window.bottom.min(monitor.bottom),
+    };
+
+    if covered.left >= covered.right || covered.top >= covered.bottom {
+        return vec![monitor];
+    }
+
+    [
+        RECT { bottom: covered.top, ..monitor },
+        RECT { top: covered.bottom, ..monitor },
+        RECT { left: monitor.left, right: covered.left, ..covered },
+        RECT { left: covered.right, right: monitor.right, ..covered },
+    ]
+    .into_iter()
+    .filter(|rect| rect.left < rect.right && rect.top < rect.bottom)
+    .collect()
+}
+
+fn run()
+{
+    let mut backdrops: HashMap<isize, Backdrop> = HashMap::new();
+
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+
+        unsafe {
+            let mut msg = MSG::default();
+            while PeekMessageW(&mut msg, None, 0, 0, PM_REMOVE).as_bool() {
+                DispatchMessageW(&msg);
+            }
+        }
+
+        let targets = {
+            let mut state = STATE.lock().unwrap();
+            state.targets.retain(|hwnd, _| unsafe { IsWindow(Some(to_hwnd(*hwnd))) }.as_bool());
+            if state.targets.is_empty() {
+                state.running = false;
+            }
+            state.targets.clone()
+        };
+
+        backdrops.retain(|hwnd, backdrop| targets.get(hwnd) == Some(&backdrop.color()));
+
+        for (hwnd, color) in &targets {
+            if !backdrops.contains_key(hwnd) {
+                match Backdrop::new(*color) {
+                    Ok(backdrop) => {
+                        backdrops.insert(*hwnd, backdrop);
+                    },
+                    Err(e) => {
+                        logging::error("backdrop", format!("Failed to create backdrop: {}", e));
+                        hide(*hwnd);
+                        continue;
+                    },
+                }
+            }
+
+            if let Some(backdrop) = backdrops.get_mut(hwnd) {
+                update(backdrop, *hwnd);
+            }
+        }
+
+        if targets.is_empty() {
+            break;
+        }
+    }
+}
+
+fn update(backdrop: &mut Backdrop, hwnd: isize)
+{
+    let target = to_hwnd(hwnd);
+
+    let visible = unsafe { IsWindowVisible(target).as_bool() && !IsIconic(target).as_bool() };
+    let placement = window_manager::frame_rect(hwnd)
+        .filter(|_| visible)
+        .and_then(|window| monitor_rect(target).map(|monitor| (monitor, window)));
+
+    match placement {
+        Some((monitor, window)) => {
+            let regions = uncovered_regions(monitor, window);
+            if regions.is_empty() {
+                backdrop.hide();
+            } else {
+                backdrop.show_below(target, monitor, regions);
+            }
+        },
+        None => backdrop.hide(),
+    }
+}
+
+fn monitor_rect(hwnd: HWND) -> Option<RECT>
+{
+    unsafe {
+        let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+        let mut info =
+            MONITORINFO { cbSize: std::mem::size_of::<MONITORINFO>() as u32, ..Default::default() };
+
+        GetMonitorInfoW(monitor, &mut info).as_bool().then_some(info.rcMonitor)
+    }
+}
+
+fn to_hwnd(hwnd: isize) -> HWND
+{
+    HWND(hwnd as *mut std::ffi::c_void)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    const MONITOR: RECT = RECT { left: 0, top: 0, right: 1920, bottom: 1080 };
+
+    fn rect(left: i32, top: i32, right: i32, bottom: i32) -> RECT
+    {
+        RECT { left, top, right, bottom }
+    }
+
+    fn area(rects: &[RECT]) -> i64
+    {
+        rects
+            .iter()
+            .map(|rect| i64::from(rect.right - rect.left) * i64::from(rect.bottom - rect.top))
+            .sum()
+    }
+
+    #[test]
+    fn centred_window_leaves_four_strips()
+    {
+        let regions = uncovered_regions(MONITOR, rect(320, 180, 1600, 900));
+
+        assert_eq!(
+            regions,
+            [
+                rect(0, 0, 1920, 180),
+                rect(0, 900, 1920, 1080),
+                rect(0, 180, 320, 900),
+                rect(1600, 180, 1920, 900),
+            ]
+        );
+    }
+
+    #[test]
+    fn window_flush_to_an_edge_leaves_three_strips()
+    {
+        let regions = uncovered_regions(MONITOR, rect(0, 180, 1280, 900));
+
+        assert_eq!(
+            regions,
+            [rect(0, 0, 1920, 180), rect(0, 900, 1920, 1080), rect(1280, 180, 1920, 900)]
+        );
+    }
+
+    #[test]
+    fn window_covering_the_monitor_leaves_nothing()
+    {
+        assert!(uncovered_regions(MONITOR, MONITOR).is_empty());
+        assert!(uncovered_regions(MONITOR, rect(-8, -8, 1928, 1088)).is_empty());
+    }
+
+    #[test]
+    fn window_off_the_monitor_leaves_the_whole_monitor()
+    {
+        assert_eq!(uncovered_regions(MONITOR, rect(1920, 0, 3840, 1080)), [MONITOR]);
+        assert_eq!(uncovered_regions(MONITOR, rect(-500, -400, -100, -50)), [MONITOR]);
+    }
+
+    #[test]
+    fn window_overhanging_the_monitor_is_clipped_to_it()
+    {
+        let regions = uncovered_regions(MONITOR, rect(-100, -50, 1000, 600));
+
+        assert_eq!(regions, [rect(0, 600, 1920, 1080), rect(1000, 0, 1920, 600)]);
+        assert_eq!(area(&regions), area(&[MONITOR]) - 1000 * 600);
+    }
+
+    #[test]
+    fn offset_monitor_uses_its_own_coordinates()
+    {
+        let monitor = rect(1920, -200, 4480, 1240);
+        let regions = uncovered_regions(monitor, rect(2560, 300, 3840, 1020));
+
+        assert_eq!(regions.len(), 4);
+        assert_eq!(area(&regions), area(&[monitor]) - 1280 * 720);
+    }
+}
@@ -0,0 +1,516 @@
+use crate::{
+    config::{Config, ConflictPolicy, ImportReport, Profile},
+    window_manager::{Anchor, DisplayInfo, WindowSize},
+};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+const FILE_NAMES: [&str; 2] = ["config.json", "Favorites.json"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MatchKind
+{
+    Process,
+    Title,
+    Regex,
+}
+
+enum ScreenMatch<'a>
+{
+    Exact(&'a DisplayInfo),
+    ByNumber(&'a DisplayInfo),
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SizeMode
+{
+    MatchScreen,
+    SpecificSize,
+    NoChange,
+}
+
+pub fn default_path() -> Option<PathBuf>
+{
+    let dir = PathBuf::from(std::env::var_os("APPDATA")?)
+        .join("Andrew Sampson")
+        .join("Borderless Gaming");
+
+    FILE_NAMES.iter().map(|name| dir.join(name)).find(|path| path.is_file())
+}
+
+pub fn import(
+    path: Option<&Path>,
+    config: &mut Config,
+    displays: &[DisplayInfo],
+    policy: ConflictPolicy,
+) -> anyhow::Result<ImportReport>
+{
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => default_path()
+            .ok_or_else(|| anyhow::anyhow!("No Borderless Gaming configuration found"))?,
+    };
+
+    let bytes = std::fs::read(&path)
+        .map_err(|e| anyhow::anyhow!("Failed to read '{}': {}", path.display(), e))?;
+    let contents = String::from_utf8_lossy(&bytes);
+    let root: Value = serde_json::from_str(contents.trim_start_matches('\u{feff}')).map_err(|_| {
+        anyhow::anyhow!(
+            "'{}' is not a JSON favorites file; the binary config.bin of newer Borderless Gaming releases cannot be read",
+            path.display()
+        )
+    })?;
+
+    let mut report = ImportReport { path: path.display().to_string(), ..Default::default() };
+    let profiles = parse(&root, config, displays, &mut report)?;
+
+    config.add_profiles(profiles, policy, &mut report);
+
+    Ok(report)
+}
+
+fn parse(
+    root: &Value,
+    config: &mut Config,
+    displays: &[DisplayInfo],
+    report: &mut ImportReport,
+) -> anyhow::Result<Vec<Profile>>
+{
+    let favorites = match root {
+        Value::Array(favorites) => favorites,
+        Value::Object(_) => {
+            if let Some(value) = field(root, &["CloseToTray"]).and_then(Value::as_bool) {
+                config.close_to_tray = value;
+            }
+            if let Some(value) = field(root, &["StartMinimized"]).and_then(Value::as_bool) {
+                config.start_minimized = value;
+            }
+
+            match field(root, &["Favorites"]) {
+                Some(Value::Array(favorites)) => favorites,
+                _ => anyhow::bail!("No favorites found in '{}'", report.path),
+            }
+        },
+        _ => anyhow::bail!("No favorites found in '{}'", report.path),
+    };
+
+    let mut profiles: Vec<Profile> = Vec::new();
+
+    for (index, favorite) in favorites.iter().enumerate() {
+        if let Some(mut profile) = parse_favorite(favorite, index, displays, &mut report.warnings) {
+            let base = profile.name.clone();
+            let mut suffix = 2;
+            while profiles.iter().any(|existing| existing.name.eq_ignore_ascii_case(&profile.name))
+            {
+                profile.name = format!("{} ({})", base, suffix);
+                suffix += 1;
+            }
+
+            profiles.push(profile);
+        }
+    }
+
+    Ok(profiles)
+}
+
+fn parse_favorite(
+    favorite: &Value,
+    index: usize,
+    displays: &[DisplayInfo],
+    warnings: &mut Vec<String>,
+) -> Option<Profile>
+{
+    let search_text = field(favorite, &["SearchText"])
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|text| !text.is_empty());
+
+    let Some(search_text) = search_text else {
+        warnings.push(format!("Favorite #{}: no search text, skipped", index + 1));
+        return None;
+    };
+
+    let mut profile = Profile { name: search_text.to_string(), ..Default::default() };
+    let mut warn = |message: String| warnings.push(format!("{}: {}", search_text, message));
+
+    match match_kind(favorite) {
+        MatchKind::Process => {
+            let process_name = search_text.strip_suffix(".exe").unwrap_or(search_text);
+            profile.name = process_name.to_string();
+            profile.process_name = Some(process_name.to_string());
+        },
+        MatchKind::Title => profile.title_contains = Some(search_text.to_string()),
+        MatchKind::Regex if !search_text.contains(|c: char| "\\^$.|?*+()[]{}".contains(c)) => {
+            profile.title_contains = Some(search_text.to_string());
+        },
+        MatchKind::Regex => {
+            warn("regular expression title matches are not supported, skipped".to_string());
+            return None;
+        },
+    }
+
+    match size_mode(favorite) {
+        SizeMode::MatchScreen => profile.resize_to_screen = true,
+        SizeMode::NoChange => profile.resize_to_screen = false,
+        SizeMode::SpecificSize => {
+            let [x, y, width, height] = ["PositionX", "PositionY", "PositionW", "PositionH"]
+                .map(|name| int(favorite, name));
+            if width > 0 && height > 0 {
+                profile.size = Some(WindowSize { width, height, anchor: Anchor::Center });
+                warn(format!(
+                    "the position ({}, {}) of its specific size is not kept, the window is centred",
+                    x, y
+                ));
+            } else {
+                profile.resize_to_screen = false;
+                warn("its specific size is empty, the window keeps its size".to_string());
+            }
+        },
+    }
+
+    let offsets = ["OffsetL", "OffsetT", "OffsetR", "OffsetB"].map(|name| int(favorite, name));
+    if offsets.iter().any(|offset| *offset != 0) {
+        let [left, top, right, bottom] = offsets;
+        warn(format!(
+            "offsets (left {}, top {}, right {}, bottom {}) are not supported",
+            left, top, right, bottom
+        ));
+    }
+
+    if let Some(screen) =
+        field(favorite, &["FavScreen", "Screen"]).filter(|screen| !screen.is_null())
+    {
+        match resolve_screen(screen, displays) {
+            ScreenMatch::Exact(display) => profile.display = Some(display.name.clone()),
+            ScreenMatch::ByNumber(display) => {
+                profile.display = Some(display.name.clone());
+                warn(format!(
+                    "its screen was matched by number only and may not be the same monitor, {} is used",
+                    display.name
+                ));
+            },
+            ScreenMatch::None => {
+                warn("its screen is not connected, the primary display is used".to_string())
+            },
+        }
+    }
+
+    profile.modifiers.always_on_top =
+        field(favorite, &["TopMost"]).and_then(Value::as_bool).unwrap_or(false);
+    profile.hide_taskbar =
+        field(favorite, &["HideWindowsTaskbar"]).and_then(Value::as_bool).unwrap_or(false);
+
+    for (name, label) in [
+        ("HideMouseCursor", "hiding the mouse cursor"),
+        ("RemoveMenus", "removing menus"),
+        ("MuteInBackground", "muting in the background"),
+        ("DelayBorderless", "delaying the borderless switch"),
+    ] {
+        if field(favorite, &[name]).and_then(Value::as_bool) == Some(true) {
+            warn(format!("{} is not supported", label));
+        }
+    }
+
+    Some(profile)
+}
+
+fn match_kind(favorite: &Value) -> MatchKind
+{
+    match field(favorite, &["Kind", "Type"]) {
+        Some(Value::Number(number)) => match number.as_u64() {
+            Some(1) => MatchKind::Title,
+            Some(2) => MatchKind::Regex,
+            _ => MatchKind::Process,
+        },
+        Some(Value::String(text)) => {
+            let text = text.to_lowercase();
+            if text.contains("regex") {
+                MatchKind::Regex
+            } else if text.contains("title") {
+                MatchKind::Title
+            } else {
+                MatchKind::Process
+            }
+        },
+        _ => MatchKind::Process,
+    }
+}
+
+fn size_mode(favorite: &Value) -> SizeMode
+{
+    match field(favorite, &["SizeMode", "Size"]) {
+        Some(Value::Number(number)) => match number.as_u64() {
+            Some(1) => SizeMode::SpecificSize,
+            Some(2) => SizeMode::NoChange,
+            _ => SizeMode::MatchScreen,
+        },
+        Some(Value::String(text)) => match text.to_lowercase().as_str() {
+            "specificsize" => SizeMode::SpecificSize,
+            "nochange" => SizeMode::NoChange,
+            _ => SizeMode::MatchScreen,
+        },
+        _ => SizeMode::MatchScreen,
+    }
+}
+
+fn resolve_screen<'a>(screen: &Value, displays: &'a [DisplayInfo]) -> ScreenMatch<'a>
+{
+    let bounds = field(screen, &["Bounds"]).unwrap_or(screen);
+    let [x, y, width, height] = ["X", "Y", "Width", "Height"].map(|name| int(bounds, name));
+
+    if width > 0
+        && height > 0
+        && let Some(display) = displays.iter().find(|display| {
+            display.x == x && display.y == y && display.width == width && display.height == height
+        })
+    {
+        return ScreenMatch::Exact(display);
+    }
+
+    let Some(device) =
+        screen.as_str().or_else(|| field(screen, &["DeviceName", "Name"]).and_then(Value::as_str))
+    else {
+        return ScreenMatch::None;
+    };
+
+    if let Some(display) =
+        displays.iter().find(|display| display.device_name.eq_ignore_ascii_case(device))
+    {
+        return ScreenMatch::Exact(display);
+    }
+
+    let number = device.trim_start_matches(r"\\.\").to_uppercase();
+    let name = format!("Display {}", number.trim_start_matches("DISPLAY"));
+    match displays.iter().find(|display| display.name.eq_ignore_ascii_case(&name)) {
+        Some(display) => ScreenMatch::ByNumber(display),
+        None => ScreenMatch::None,
+    }
+}
+
+fn field<'a>(value: &'a Value, names: &[&str]) -> Option<&'a Value>
+{
+    let object = value.as_object()?;
+
+    names.iter().find_map(|name| {
+        object.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value)
+    })
+}
+
+fn int(value: &Value, name: &str) -> i32
+{
+    field(value, &[name])
+        .and_then(Value::as_i64)
+        .and_then(|value| i32::try_from(value).ok())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::window_manager::FrameMode;
+    use serde_json::json;
+
+    fn displays() -> Vec<DisplayInfo>
+    {
+        vec![
+            DisplayInfo {
+                name: "Display 1".to_string(),
+                device_name: r"\\.\DISPLAY2".to_string(),
+                x: 0,
+                y: 0,
+                width: 1920,
+                height: 1080,
+                is_primary: true,
+            },
+            DisplayInfo {
+                name: "Display 2".to_string(),
+                device_name: r"\\.\DISPLAY1".to_string(),
+                x: 1920,
+                y: 0,
+                width: 2560,
+                height: 1440,
+                is_primary: false,
+            },
+        ]
+    }
+
+    fn sample(name: &str) -> PathBuf
+    {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("samples").join("borderless-gaming").join(name)
+    }
+
+    fn import_sample(name: &str, config: &mut Config) -> ImportReport
+    {
+        import(Some(&sample(name)), config, &displays(), ConflictPolicy::Skip).unwrap()
+    }
+
+    fn favorite(value: Value) -> (Option<Profile>, Vec<String>)
+    {
+        let mut warnings = Vec::new();
+        let profile = parse_favorite(&value, 0, &displays(), &mut warnings);
+        (profile, warnings)
+    }
+
+    #[test]
+    fn imports_the_favorites_sample()
+    {
+        let mut config = Config::default();
+        let report = import_sample("Favorites.json", &mut config);
+
+        assert_eq!(report.imported, ["eldenring", "Diablo II", "starcraft"]);
+        assert_eq!(
+            report.warnings,
+            [
+                "Diablo II: offsets (left 0, top 30, right 0, bottom 0) are not supported",
+                "Diablo II: hiding the mouse cursor is not supported",
+                "starcraft: the position (0, 0) of its specific size is not kept, the window is centred",
+                "^Minecraft\\* [0-9.]+$: regular expression title matches are not supported, skipped",
+            ]
+        );
+
+        let elden_ring = config.find_profile("eldenring").unwrap();
+        assert_eq!(elden_ring.process_name.as_deref(), Some("eldenring"));
+        assert_eq!(elden_ring.title_contains, None);
+        assert!(elden_ring.resize_to_screen);
+        assert_eq!(elden_ring.size, None);
+        assert_eq!(elden_ring.frame_mode, FrameMode::Full);
+        assert_eq!(elden_ring.display, None);
+        assert!(!elden_ring.hide_taskbar);
+
+        let diablo = config.find_profile("Diablo II").unwrap();
+        assert_eq!(diablo.process_name, None);
+        assert_eq!(diablo.title_contains.as_deref(), Some("Diablo II"));
+        assert!(!diablo.resize_to_screen);
+
+        let starcraft = config.find_profile("starcraft").unwrap();
+        assert_eq!(starcraft.process_name.as_deref(), Some("starcraft"));
+        assert_eq!(
+            starcraft.size,
+            Some(WindowSize { width: 1600, height: 900, anchor: Anchor::Center })
+        );
+        assert_eq!(starcraft.frame_mode, FrameMode::Full);
+        assert!(starcraft.hide_taskbar);
+    }
+
+    #[test]
+    fn imports_the_config_sample()
+    {
+        let mut config = Config::default();
+        let report = import_sample("config.json", &mut config);
+
+        assert_eq!(report.imported, ["witcher3", "Path of Exile", "Final Fantasy XIV"]);
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+        assert!(config.close_to_tray);
+        assert!(!config.start_minimized);
+
+        let witcher = config.find_profile("witcher3").unwrap();
+        assert_eq!(witcher.process_name.as_deref(), Some("witcher3"));
+        assert!(witcher.resize_to_screen);
+        assert_eq!(witcher.display.as_deref(), Some("Display 2"));
+        assert_eq!(witcher.frame_mode, FrameMode::Full);
+        assert!(witcher.modifiers.always_on_top);
+
+        let path_of_exile = config.find_profile("Path of Exile").unwrap();
+        assert_eq!(path_of_exile.title_contains.as_deref(), Some("Path of Exile"));
+        assert!(!path_of_exile.resize_to_screen);
+
+        let final_fantasy = config.find_profile("Final Fantasy XIV").unwrap();
+        assert_eq!(final_fantasy.title_contains.as_deref(), Some("Final Fantasy XIV"));
+        assert_eq!(final_fantasy.process_name, None);
+    }
+
+    #[test]
+    fn reimporting_keeps_existing_profiles()
+    {
+        let mut config = Config::default();
+        import_sample("config.json", &mut config);
+        let report = import_sample("config.json", &mut config);
+
+        assert!(report.imported.is_empty());
+        assert_eq!(report.warnings.len(), 3);
+        assert_eq!(config.profiles.len(), 3);
+    }
+
+    #[test]
+    fn skips_favorites_without_search_text()
+    {
+        let (profile, warnings) = favorite(json!({ "Kind": 0, "SearchText": "  " }));
+
+        assert!(profile.is_none());
+        assert_eq!(warnings, ["Favorite #1: no search text, skipped"]);
+    }
+
+    #[test]
+    fn warns_about_empty_specific_sizes()
+    {
+        let (profile, warnings) = favorite(json!({ "SearchText": "game.exe", "SizeMode": 1 }));
+        let profile = profile.unwrap();
+
+        assert!(!profile.resize_to_screen);
+        assert_eq!(profile.size, None);
+        assert_eq!(warnings, ["game.exe: its specific size is empty, the window keeps its size"]);
+    }
+
+    #[test]
+    fn resolves_screens_by_bounds_before_device_name()
+    {
+        let (profile, warnings) = favorite(json!({
+            "SearchText": "game",
+            "Screen": {
+                "DeviceName": r"\\.\DISPLAY1",
+                "Bounds": { "X": 0, "Y": 0, "Width": 1920, "Height": 1080 }
+            }
+        }));
+
+        assert_eq!(profile.unwrap().display.as_deref(), Some("Display 1"));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn resolves_screens_by_device_name()
+    {
+        let (profile, warnings) = favorite(json!({
+            "SearchText": "game",
+            "Screen": { "DeviceName": r"\\.\DISPLAY1", "Bounds": { "X": 0, "Y": 0 } }
+        }));
+
+        assert_eq!(profile.unwrap().display.as_deref(), Some("Display 2"));
+        assert!(warnings.is_empty());
+
+        let (profile, _) = favorite(json!({ "SearchText": "game", "Screen": r"\\.\display2" }));
+        assert_eq!(profile.unwrap().display.as_deref(), Some("Display 1"));
+    }
+
+    #[test]
+    fn falls_back_to_the_display_number_with_a_warning()
+    {
+        let mut displays = displays();
+        displays[0].device_name = r"\\.\DISPLAY5".to_string();
+
+        let mut warnings = Vec::new();
+        let profile = parse_favorite(
+            &json!({ "SearchText": "game", "Screen": r"\\.\DISPLAY2" }),
+            0,
+            &displays,
+            &mut warnings,
+        );
+
+        assert_eq!(profile.unwrap().display.as_deref(), Some("Display 2"));
+        assert_eq!(
+            warnings,
+            ["game: its screen was matched by number only and may not be the same monitor, Display 2 is used"]
+        );
+    }
+
+    #[test]
+    fn warns_about_disconnected_screens()
+    {
+        let (profile, warnings) =
+            favorite(json!({ "SearchText": "game", "Screen": r"\\.\DISPLAY9" }));
+
+        assert_eq!(profile.unwrap().display, None);
+        assert_eq!(warnings, ["game: its screen is not connected, the primary display is used"]);
+    }
+}
@@ -0,0 +1,353 @@
+use crate::{
+    config::{Config, ConflictPolicy, ImportReport, Profile},
+    window_manager::DisplayInfo,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format
+{
+    Json,
+    Toml,
+}
+
+impl Format
+{
+    fn from_path(path: &Path) -> Self
+    {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("toml") => Self::Toml,
+            _ => Self::Json,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Bundle
+{
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    profiles: Vec<BundledProfile>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundledProfile
+{
+    #[serde(flatten)]
+    profile: Profile,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    display_info: Option<DisplayRef>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DisplayRef
+{
+    width: i32,
+    height: i32,
+    is_primary: bool,
+}
+
+pub fn default_path() -> String
+{
+    std::env::var_os("USERPROFILE")
+        .map(|home| {
+            PathBuf::from(home)
+                .join("Documents")
+                .join("ihateborders-profiles.json")
+                .display()
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+pub fn is_bundle_path(path: &Path) -> bool
+{
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| ["json", "toml"].iter().any(|e| extension.eq_ignore_ascii_case(e)))
+}
+
+pub fn export(path: &Path, profiles: &[Profile], displays: &[DisplayInfo]) -> anyhow::Result<()>
+{
+    let bundle = Bundle {
+        version: BUNDLE_VERSION,
+        profiles: profiles
+            .iter()
+            .map(|profile| BundledProfile {
+                profile: profile.clone(),
+                display_info: profile
+                    .display
+                    .as_deref()
+                    .and_then(|name| {
+                        displays.iter().find(|display| display.name.eq_ignore_ascii_case(name))
+                    })
+                    .map(|display| DisplayRef {
+                        width: display.width,
+                        height: display.height,
+                        is_primary: display.is_primary,
+                    }),
+            })
+            .collect(),
+    };
+
+    let contents = match Format::from_path(path) {
+        Format::Json => serde_json::to_string_pretty(&bundle)?,
+        Format::Toml => toml::to_string_pretty(&bundle)?,
+    };
+
+    std::fs::write(path, contents)
+        .map_err(|e| anyhow::anyhow!("Failed to write '{}': {}", path.display(), e))
+}
+
+pub fn import(
+    path: &Path,
+    config: &mut Config,
+    displays: &[DisplayInfo],
+    policy: ConflictPolicy,
+) -> anyhow::Result<ImportReport>
+{
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read '{}': {}", path.display(), e))?;
+
+    let bundle: Bundle = match Format::from_path(path) {
+        Format::Json => serde_json::from_str(contents.trim_start_matches('\u{feff}'))
+            .map_err(|e| anyhow::anyhow!("Invalid profile file '{}': {}", path.display(), e))?,
+        Format::Toml => toml::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("Invalid profile file '{}': {}", path.display(), e))?,
+    };
+
+    if bundle.version > BUNDLE_VERSION {
+        anyhow::bail!(
+            "'{}' was written by a newer version of ihateborders (format {})",
+            path.display(),
+            bundle.version
+        );
+    }
+
+    let mut report = ImportReport { path: path.display().to_string(), ..Default::default() };
+    let mut profiles = Vec::new();
+
+    for BundledProfile { mut profile, display_info } in bundle.profiles {
+        if profile.name.trim().is_empty() {
+            report.warnings.push("A profile without a name was skipped".to_string());
+            continue;
+        }
+
+        if let Some(name) = profile.display.take() {
+            profile.display =
+                Some(resolve_display(&profile.name, name, display_info, displays, &mut report));
+        }
+
+        profiles.push(profile);
+    }
+
+    config.add_profiles(profiles, policy, &mut report);
+
+    Ok(report)
+}
+
+fn resolve_display(
+    profile: &str,
+    name: String,
+    info: Option<DisplayRef>,
+    displays: &[DisplayInfo],
+    report: &mut ImportReport,
+) -> String
+{
+    if name.eq_ignore_ascii_case("primary") || name.eq_ignore_ascii_case("largest") {
+        return name;
+    }
+
+    let named = displays.iter().find(|display| display.name.eq_ignore_ascii_case(&name));
+
+    let Some(info) = info else {
+        return match named {
+            Some(display) => display.name.clone(),
+            None => {
+                report.warnings.push(format!(
+                    "{}: {} is not connected, the primary display is used",
+                    profile, name
+                ));
+                "primary".to_string()
+            },
+        };
+    };
+
+    let same_size =
+        |display: &&DisplayInfo| display.width == info.width && display.height == info.height;
+
+    if let Some(display) = named.filter(same_size) {
+        return display.name.clone();
+    }
+
+    if let Some(display) = displays.iter().find(same_size) {
+        report.warnings.push(format!(
+            "{}: {} ({}x{}) is now {}",
+            profile, name, info.width, info.height, display.name
+        ));
+        return display.name.clone();
+    }
+
+    let fallback = if info.is_primary { "primary" } else { "largest" };
+    report.warnings.push(format!(
+        "{}: no {}x{} display like {} is connected, the {} display is used",
+        profile, info.width, info.height, name, fallback
+    ));
+
+    fallback.to_string()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn displays() -> Vec<DisplayInfo>
+    {
+        vec![
+            DisplayInfo {
+                name: "Display 1".to_string(),
+                device_name: r"\\.\DISPLAY2".to_string(),
+                x: 0,
+                y: 0,
+                width: 1920,
+                height: 1080,
+                is_primary: true,
+            },
+            DisplayInfo {
+                name: "Display 2".to_string(),
+                device_name: r"\\.\DISPLAY1".to_string(),
+                x: 1920,
+                y: 0,
+                width: 2560,
+                height: 1440,
+                is_primary: false,
+            },
+        ]
+    }
+
+    fn display_ref(width: i32, height: i32, is_primary: bool) -> Option<DisplayRef>
+    {
+        Some(DisplayRef { width, height, is_primary })
+    }
+
+    fn resolve(name: &str, info: Option<DisplayRef>) -> (String, Vec<String>)
+    {
+        let mut report = ImportReport::default();
+        let resolved = resolve_display("Game", name.to_string(), info, &displays(), &mut report);
+        (resolved, report.warnings)
+    }
+
+    fn profile(name: &str, display: Option<&str>) -> Profile
+    {
+        Profile {
+            name: name.to_string(),
+            process_name: Some(name.to_lowercase()),
+            display: display.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn keeps_primary_and_largest()
+    {
+        assert_eq!(resolve("primary", None), ("primary".to_string(), vec![]));
+        assert_eq!(
+            resolve("Largest", display_ref(800, 600, false)),
+            ("Largest".to_string(), vec![])
+        );
+    }
+
+    #[test]
+    fn keeps_a_connected_display_without_size()
+    {
+        assert_eq!(resolve("display 2", None), ("Display 2".to_string(), vec![]));
+    }
+
+    #[test]
+    fn falls_back_to_primary_for_a_missing_display_without_size()
+    {
+        assert_eq!(
+            resolve("Display 3", None),
+            (
+                "primary".to_string(),
+                vec!["Game: Display 3 is not connected, the primary display is used".to_string()]
+            )
+        );
+    }
+
+    #[test]
+    fn keeps_a_display_with_the_same_size()
+    {
+        assert_eq!(
+            resolve("Display 2", display_ref(2560, 1440, false)),
+            ("Display 2".to_string(), vec![])
+        );
+    }
+
+    #[test]
+    fn follows_the_size_to_another_display()
+    {
+        assert_eq!(
+            resolve("Display 1", display_ref(2560, 1440, false)),
+            (
+                "Display 2".to_string(),
+                vec!["Game: Display 1 (2560x1440) is now Display 2".to_string()]
+            )
+        );
+    }
+
+    #[test]
+    fn falls_back_when_no_display_has_the_size()
+    {
+        assert_eq!(
+            resolve("Display 3", display_ref(3840, 2160, true)),
+            (
+                "primary".to_string(),
+                vec![
+                    "Game: no 3840x2160 display like Display 3 is connected, the primary display is used"
+                        .to_string()
+                ]
+            )
+        );
+        assert_eq!(
+            resolve("Display 3", display_ref(3840, 2160, false)),
+            (
+                "largest".to_string(),
+                vec![
+                    "Game: no 3840x2160 display like Display 3 is connected, the largest display is used"
+                        .to_string()
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn round_trips_through_a_different_monitor_set()
+    {
+        let path = std::env::temp_dir()
+            .join(format!("ihateborders-bundle-test-{}.toml", std::process::id()));
+        let profiles = [profile("Witcher", Some("Display 2")), profile("Doom", Some("Display 1"))];
+        export(&path, &profiles, &displays()).unwrap();
+
+        let swapped = vec![DisplayInfo { name: "Display 1".to_string(), ..displays().remove(1) }];
+        let mut config = Config::default();
+        let report = import(&path, &mut config, &swapped, ConflictPolicy::Skip).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(report.imported, ["Witcher", "Doom"]);
+        assert_eq!(
+            report.warnings,
+            [
+                "Witcher: Display 2 (2560x1440) is now Display 1",
+                "Doom: no 1920x1080 display like Display 1 is connected, the primary display is used",
+            ]
+        );
+        assert_eq!(config.find_profile("Witcher").unwrap().display.as_deref(), Some("Display 1"));
+        assert_eq!(config.find_profile("Doom").unwrap().display.as_deref(), Some("primary"));
+    }
+}
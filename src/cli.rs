@@ -0,0 +1,251 @@
+use crate::{
+    config::ConflictPolicy,
+    launcher::{ExitAction, RunOptions},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command
+{
+    Show,
+    ApplyProfile(String),
+    Run(RunOptions),
+    ImportBorderlessGaming(Option<String>),
+    ExportProfiles
+    {
+        path: String,
+        names: Vec<String>,
+    },
+    ImportProfiles
+    {
+        path: String,
+        on_conflict: Option<ConflictPolicy>,
+    },
+    Relaunch
+    {
+        after: u32,
+        select: Option<isize>,
+    },
+}
+
+impl Command
+{
+    pub fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self>
+    {
+        let mut args = args.into_iter();
+
+        match args.next().as_deref() {
+            None | Some("show") => Ok(Self::Show),
+            Some("apply") => {
+                let name = args
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("Usage: ihateborders apply <profile>"))?;
+                Ok(Self::ApplyProfile(name))
+            },
+            Some("run") => Self::parse_run(args),
+            Some("import-borderless-gaming") => {
+                Ok(Self::ImportBorderlessGaming(args.next().map(absolute)))
+            },
+            Some("export") => {
+                let path = args.next().ok_or_else(|| {
+                    anyhow::anyhow!("Usage: ihateborders export <file.json|file.toml> [profile...]")
+                })?;
+                Ok(Self::ExportProfiles { path: absolute(path), names: args.collect() })
+            },
+            Some("import") => Self::parse_import(args),
+            Some("relaunch") => Self::parse_relaunch(args),
+            Some(other) => anyhow::bail!("Unknown command '{}'", other),
+        }
+    }
+
+    fn parse_import(mut args: impl Iterator<Item = String>) -> anyhow::Result<Self>
+    {
+        const USAGE: &str = "Usage: ihateborders import [--on-conflict <skip|rename|overwrite>] <file.json|file.toml>";
+
+        let mut on_conflict = None;
+
+        let path = loop {
+            match args.next() {
+                Some(arg) if arg == "--on-conflict" => {
+                    let policy = args.next().ok_or_else(|| anyhow::anyhow!(USAGE))?;
+                    on_conflict = Some(ConflictPolicy::parse(&policy)?);
+                },
+                Some(arg) if arg.starts_with("--") => anyhow::bail!("Unknown option '{}'", arg),
+                Some(arg) => break arg,
+                None => anyhow::bail!(USAGE),
+            }
+        };
+
+        Ok(Self::ImportProfiles { path: absolute(path), on_conflict })
+    }
+
+    fn parse_relaunch(mut args: impl Iterator<Item = String>) -> anyhow::Result<Self>
+    {
+        const USAGE: &str = "Usage: ihateborders relaunch --after <pid> [--select <hwnd>]";
+
+        let mut after = None;
+        let mut select = None;
+
+        while let Some(arg) = args.next() {
+            let value = args.next().ok_or_else(|| anyhow::anyhow!(USAGE))?;
+            match arg.as_str() {
+                "--after" => after = Some(value.parse().map_err(|_| anyhow::anyhow!(USAGE))?),
+                "--select" => select = Some(value.parse().map_err(|_| anyhow::anyhow!(USAGE))?),
+                _ => anyhow::bail!("Unknown option '{}'", arg),
+            }
+        }
+
+        Ok(Self::Relaunch { after: after.ok_or_else(|| anyhow::anyhow!(USAGE))?, select })
+    }
+
+    fn parse_run(mut args: impl Iterator<Item = String>) -> anyhow::Result<Self>
+    {
+        const USAGE: &str = "Usage: ihateborders run [--profile <name>] [--on-exit <none|restore|quit>] -- <program> [args...]";
+
+        let mut options = RunOptions::default();
+
+        let program = loop {
+            match args.next() {
+                Some(arg) if arg == "--profile" => {
+                    options.profile = Some(args.next().ok_or_else(|| anyhow::anyhow!(USAGE))?);
+                },
+                Some(arg) if arg == "--on-exit" => {
+                    let action = args.next().ok_or_else(|| anyhow::anyhow!(USAGE))?;
+                    options.on_exit = ExitAction::parse(&action)?;
+                },
+                Some(arg) if arg == "--" => {
+                    break args.next().ok_or_else(|| anyhow::anyhow!(USAGE))?;
+                },
+                Some(arg) if arg.starts_with("--") => anyhow::bail!("Unknown option '{}'", arg),
+                Some(arg) => break arg,
+                None => anyhow::bail!(USAGE),
+            }
+        };
+
+        let path = std::path::Path::new(&program);
+        options.program = if path.exists() {
+            std::path::absolute(path)?.to_string_lossy().into_owned()
+        } else {
+            program
+        };
+        options.args = args.collect();
+
+        Ok(Self::Run(options))
+    }
+}
+
+fn absolute(path: String) -> String
+{
+    std::path::absolute(&path).map(|path| path.to_string_lossy().into_owned()).unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn parse(args: &[&str]) -> anyhow::Result<Command>
+    {
+        Command::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn run(args: &[&str]) -> RunOptions
+    {
+        match parse(args).unwrap() {
+            Command::Run(options) => options,
+            other => panic!("expected run, got {:?}", other),
+        }
+    }
+
+    fn strings(args: &[&str]) -> Vec<String>
+    {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_commands()
+    {
+        for (args, command) in [
+            (&[][..], Command::Show),
+            (&["show"], Command::Show),
+            (&["apply", "Elden Ring"], Command::ApplyProfile("Elden Ring".to_string())),
+            (&["relaunch", "--after", "42"], Command::Relaunch { after: 42, select: None }),
+            (
+                &["relaunch", "--select", "-1234", "--after", "42"],
+                Command::Relaunch { after: 42, select: Some(-1234) },
+            ),
+            (
+                &["import", "--on-conflict", "rename", "profiles.toml"],
+                Command::ImportProfiles {
+                    path: absolute("profiles.toml".to_string()),
+                    on_conflict: Some(ConflictPolicy::Rename),
+                },
+            ),
+        ] {
+            assert_eq!(parse(args).unwrap(), command, "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_and_incomplete_commands()
+    {
+        const RELAUNCH: &str = "Usage: ihateborders relaunch --after <pid> [--select <hwnd>]";
+
+        for (args, error) in [
+            (&["hide"][..], "Unknown command 'hide'"),
+            (&["apply"], "Usage: ihateborders apply <profile>"),
+            (&["relaunch"], RELAUNCH),
+            (&["relaunch", "--after"], RELAUNCH),
+            (&["relaunch", "--after", "me"], RELAUNCH),
+            (&["relaunch", "--select", "1"], RELAUNCH),
+            (&["relaunch", "--after", "42", "--select", "window"], RELAUNCH),
+            (&["relaunch", "--after", "42", "--wait", "1"], "Unknown option '--wait'"),
+            (&["import", "--force", "profiles.json"], "Unknown option '--force'"),
+        ] {
+            assert_eq!(parse(args).unwrap_err().to_string(), error, "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn run_keeps_arguments_after_the_separator()
+    {
+        let options =
+            run(&["run", "--profile", "Game", "--", "game.exe", "--flag", "--profile", "x"]);
+
+        assert_eq!(options.program, "game.exe");
+        assert_eq!(options.args, strings(&["--flag", "--profile", "x"]));
+        assert_eq!(options.profile.as_deref(), Some("Game"));
+        assert_eq!(options.on_exit, ExitAction::None);
+    }
+
+    #[test]
+    fn run_takes_the_first_positional_as_the_program()
+    {
+        let options = run(&["run", "--on-exit", "restore", "game.exe", "-windowed"]);
+
+        assert_eq!(options.program, "game.exe");
+        assert_eq!(options.args, strings(&["-windowed"]));
+        assert_eq!(options.profile, None);
+        assert_eq!(options.on_exit, ExitAction::Restore);
+    }
+
+    #[test]
+    fn run_rejects_missing_programs_and_unknown_options()
+    {
+        const USAGE: &str = "Usage: ihateborders run [--profile <name>] [--on-exit <none|restore|quit>] -- <program> [args...]";
+
+        for (args, error) in [
+            (&["run"][..], USAGE),
+            (&["run", "--profile", "Game"], USAGE),
+            (&["run", "--profile", "Game", "--"], USAGE),
+            (&["run", "--profile"], USAGE),
+            (&["run", "--on-exit"], USAGE),
+            (&["run", "--wait", "game.exe"], "Unknown option '--wait'"),
+            (
+                &["run", "--on-exit", "later", "game.exe"],
+                "Unknown exit action 'later' (expected none, restore or quit)",
+            ),
+        ] {
+            assert_eq!(parse(args).unwrap_err().to_string(), error, "{:?}", args);
+        }
+    }
+}
@@ -0,0 +1,569 @@
+use crate::{
+    hotkeys::{self, HotkeyAction},
+    logging::{self, LogLevel},
+    window_manager::{
+        self, BorderlessEffects, DisplayInfo, FrameMode, WindowInfo, WindowModifiers, WindowSize,
+        WindowSort,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config
+{
+    pub resize_to_screen: bool,
+    pub display: Option<String>,
+    pub frame_mode: FrameMode,
+    pub hide_taskbar: bool,
+    pub lock_cursor: bool,
+    pub window_size: Option<WindowSize>,
+    pub custom_sizes: Vec<(i32, i32)>,
+    pub backdrop: Option<[u8; 3]>,
+    pub close_to_tray: bool,
+    pub start_minimized: bool,
+    pub profiles: Vec<Profile>,
+    pub favorites: Vec<Favorite>,
+    pub hotkeys: BTreeMap<HotkeyAction, String>,
+    pub filters: WindowFilters,
+    pub sort: WindowSort,
+    pub group_by_process: bool,
+    pub import_conflict: ConflictPolicy,
+    pub log_level: LogLevel,
+    pub redact_titles: bool,
+}
+
+impl Default for Config
+{
+    fn default() -> Self
+    {
+        Self {
+            resize_to_screen: true,
+            display: None,
+            frame_mode: FrameMode::default(),
+            hide_taskbar: false,
+            lock_cursor: false,
+            window_size: None,
+            custom_sizes: Vec::new(),
+            backdrop: None,
+            close_to_tray: false,
+            start_minimized: false,
+            profiles: Vec::new(),
+            favorites: Vec::new(),
+            hotkeys: hotkeys::default_bindings(),
+            filters: WindowFilters::default(),
+            sort: WindowSort::default(),
+            group_by_process: false,
+            import_conflict: ConflictPolicy::default(),
+            log_level: LogLevel::default(),
+            redact_titles: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy
+{
+    #[default]
+    Skip,
+    Rename,
+    Overwrite,
+}
+
+impl ConflictPolicy
+{
+    pub const ALL: [Self; 3] = [Self::Skip, Self::Rename, Self::Overwrite];
+
+    pub fn label(self) -> &'static str
+    {
+        match self {
+            Self::Skip => "Skip existing",
+            Self::Rename => "Rename",
+            Self::Overwrite => "Overwrite",
+        }
+    }
+
+    pub fn parse(text: &str) -> anyhow::Result<Self>
+    {
+        match text.to_ascii_lowercase().as_str() {
+            "skip" => Ok(Self::Skip),
+            "rename" => Ok(Self::Rename),
+            "overwrite" => Ok(Self::Overwrite),
+            _ => anyhow::bail!(
+                "Unknown conflict policy '{}' (expected skip, rename or overwrite)",
+                text
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport
+{
+    pub path: String,
+    pub imported: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowFilters
+{
+    pub show_tool_windows: bool,
+    pub show_owned_windows: bool,
+    pub show_cloaked_windows: bool,
+    pub show_untitled_windows: bool,
+    pub show_minimized_windows: bool,
+    pub exclude: Vec<String>,
+}
+
+impl Default for WindowFilters
+{
+    fn default() -> Self
+    {
+        Self {
+            show_tool_windows: false,
+            show_owned_windows: false,
+            show_cloaked_windows: false,
+            show_untitled_windows: false,
+            show_minimized_windows: true,
+            exclude: vec!["Program Manager".to_string()],
+        }
+    }
+}
+
+impl WindowFilters
+{
+    pub fn is_excluded(&self, title: &str, process_name: &str) -> bool
+    {
+        let title = title.to_lowercase();
+
+        self.exclude.iter().map(|entry| entry.trim()).filter(|entry| !entry.is_empty()).any(
+            |entry| {
+                // Process names are listed without their extension, but people type "game.exe".
+                let split = entry.len().saturating_sub(4);
+                let program = match entry.get(split..) {
+                    Some(extension) if extension.eq_ignore_ascii_case(".exe") => &entry[..split],
+                    _ => entry,
+                };
+
+                process_name.eq_ignore_ascii_case(program)
+                    || title.starts_with(&entry.to_lowercase())
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile
+{
+    pub name: String,
+    pub process_name: Option<String>,
+    pub title_contains: Option<String>,
+    pub class_name: Option<String>,
+    pub exe_path: Option<String>,
+    pub resize_to_screen: bool,
+    pub display: Option<String>,
+    pub frame_mode: FrameMode,
+    pub hide_taskbar: bool,
+    pub lock_cursor: bool,
+    pub size: Option<WindowSize>,
+    pub backdrop: Option<[u8; 3]>,
+    pub modifiers: WindowModifiers,
+}
+
+impl Default for Profile
+{
+    fn default() -> Self
+    {
+        Self {
+            name: String::new(),
+            process_name: None,
+            title_contains: None,
+            class_name: None,
+            exe_path: None,
+            resize_to_screen: true,
+            display: None,
+            frame_mode: FrameMode::default(),
+            hide_taskbar: false,
+            lock_cursor: false,
+            size: None,
+            backdrop: None,
+            modifiers: WindowModifiers::default(),
+        }
+    }
+}
+
+impl Profile
+{
+    pub fn matches(&self, window: &WindowInfo) -> bool
+    {
+        if self.process_name.is_none()
+            && self.title_contains.is_none()
+            && self.class_name.is_none()
+            && self.exe_path.is_none()
+        {
+            return false;
+        }
+
+        let process_matches = self
+            .process_name
+            .as_ref()
+            .is_none_or(|process_name| process_name.eq_ignore_ascii_case(&window.process_name));
+
+        let title_matches = self
+            .title_contains
+            .as_ref()
+            .is_none_or(|pattern| window.title.to_lowercase().contains(&pattern.to_lowercase()));
+
+        let class_matches = self
+            .class_name
+            .as_ref()
+            .is_none_or(|class_name| class_name.eq_ignore_ascii_case(&window.class_name));
+
+        let path_matches = self.exe_path.as_ref().is_none_or(|exe_path| {
+            window.exe_path.as_ref().is_some_and(|path| path.eq_ignore_ascii_case(exe_path))
+        });
+
+        process_matches && title_matches && class_matches && path_matches
+    }
+
+    pub fn resolve_display<'a>(&self, displays: &'a [DisplayInfo]) -> Option<&'a DisplayInfo>
+    {
+        window_manager::find_display(displays, self.display.as_deref())
+    }
+
+    pub fn effects(&self) -> BorderlessEffects
+    {
+        BorderlessEffects {
+            hide_taskbar: self.hide_taskbar,
+            lock_cursor: self.lock_cursor,
+            backdrop: self.backdrop,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Favorite
+{
+    pub process_name: String,
+    pub title_contains: Option<String>,
+    pub exe_path: Option<String>,
+    pub resize_to_screen: bool,
+    pub display: Option<String>,
+    pub frame_mode: FrameMode,
+}
+
+impl Default for Favorite
+{
+    fn default() -> Self
+    {
+        Self {
+            process_name: String::new(),
+            title_contains: None,
+            exe_path: None,
+            resize_to_screen: true,
+            display: None,
+            frame_mode: FrameMode::default(),
+        }
+    }
+}
+
+impl Favorite
+{
+    pub fn from_window(
+        window: &WindowInfo,
+        resize_to_screen: bool,
+        display: Option<String>,
+        frame_mode: FrameMode,
+    ) -> Self
+    {
+        Self {
+            process_name: window.process_name.clone(),
+            title_contains: None,
+            exe_path: window.exe_path.clone(),
+            resize_to_screen,
+            display,
+            frame_mode,
+        }
+    }
+
+    pub fn matches(&self, window: &WindowInfo) -> bool
+    {
+        self.as_profile().matches(window)
+    }
+
+    pub fn as_profile(&self) -> Profile
+    {
+        Profile {
+            name: self.process_name.clone(),
+            process_name: Some(self.process_name.clone()),
+            title_contains: self.title_contains.clone().filter(|pattern| !pattern.is_empty()),
+            resize_to_screen: self.resize_to_screen,
+            display: self.display.clone(),
+            frame_mode: self.frame_mode,
+            ..Default::default()
+        }
+    }
+}
+
+impl Config
+{
+    pub fn dir() -> Option<PathBuf>
+    {
+        std::env::var_os("APPDATA").map(|app_data| PathBuf::from(app_data).join("ihateborders"))
+    }
+
+    pub fn path() -> Option<PathBuf>
+    {
+        Self::dir().map(|dir| dir.join("config.json"))
+    }
+
+    pub fn load() -> Self
+    {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                logging::error(
+                    "config",
+                    format!("Failed to parse config '{}': {}", path.display(), e),
+                );
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()>
+    {
+        let path = Self::path().ok_or_else(|| anyhow::anyhow!("APPDATA is not set"))?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    pub fn favorite_for(&self, window: &WindowInfo) -> Option<&Favorite>
+    {
+        self.favorites.iter().find(|favorite| favorite.matches(window))
+    }
+
+    pub fn placement_for(&self, window: Option<&WindowInfo>) -> (bool, Option<String>, FrameMode)
+    {
+        match window.and_then(|window| self.favorite_for(window)) {
+            Some(favorite) => {
+                (favorite.resize_to_screen, favorite.display.clone(), favorite.frame_mode)
+            },
+            None => (self.resize_to_screen, self.display.clone(), self.frame_mode),
+        }
+    }
+
+    // Favorites only carry placement, so every toggle outside a profile uses the global effects.
+    pub fn effects(&self) -> BorderlessEffects
+    {
+        BorderlessEffects {
+            hide_taskbar: self.hide_taskbar,
+            lock_cursor: self.lock_cursor,
+            backdrop: self.backdrop,
+        }
+    }
+
+    pub fn find_profile(&self, name: &str) -> Option<&Profile>
+    {
+        self.profiles.iter().find(|profile| profile.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn profiles_named(&self, names: &[String]) -> anyhow::Result<Vec<Profile>>
+    {
+        if names.is_empty() {
+            return Ok(self.profiles.clone());
+        }
+
+        names
+            .iter()
+            .map(|name| {
+                self.find_profile(name)
+                    .cloned()
+                    .ok_or_else(|| anyhow::anyhow!("Unknown profile '{}'", name))
+            })
+            .collect()
+    }
+
+    pub fn add_profiles(
+        &mut self,
+        profiles: Vec<Profile>,
+        policy: ConflictPolicy,
+        report: &mut ImportReport,
+    )
+    {
+        for mut profile in profiles {
+            let existing = self
+                .profiles
+                .iter()
+                .position(|other| other.name.eq_ignore_ascii_case(&profile.name));
+
+            match (existing, policy) {
+                (None, _) => {},
+                (Some(_), ConflictPolicy::Skip) => {
+                    report.warnings.push(format!(
+                        "{}: a profile with this name already exists and was kept",
+                        profile.name
+                    ));
+                    continue;
+                },
+                (Some(_), ConflictPolicy::Rename) => {
+                    let base = profile.name.clone();
+                    let mut suffix = 2;
+                    while self.find_profile(&profile.name).is_some() {
+                        profile.name = format!("{} ({})", base, suffix);
+                        suffix += 1;
+                    }
+                },
+                (Some(index), ConflictPolicy::Overwrite) => {
+                    self.profiles.remove(index);
+                },
+            }
+
+            report.imported.push(profile.name.clone());
+            self.profiles.push(profile);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn profile(name: &str, process_name: &str) -> Profile
+    {
+        Profile {
+            name: name.to_string(),
+            process_name: Some(process_name.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn config_with(profiles: &[Profile]) -> Config
+    {
+        Config { profiles: profiles.to_vec(), ..Default::default() }
+    }
+
+    fn add(config: &mut Config, profiles: &[Profile], policy: ConflictPolicy) -> ImportReport
+    {
+        let mut report = ImportReport::default();
+        config.add_profiles(profiles.to_vec(), policy, &mut report);
+        report
+    }
+
+    fn names(config: &Config) -> Vec<(&str, Option<&str>)>
+    {
+        config
+            .profiles
+            .iter()
+            .map(|profile| (profile.name.as_str(), profile.process_name.as_deref()))
+            .collect()
+    }
+
+    fn excluding(entries: &[&str]) -> WindowFilters
+    {
+        WindowFilters {
+            exclude: entries.iter().map(|entry| entry.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn excludes_titles_by_case_insensitive_prefix()
+    {
+        let filters = excluding(&["program manager", "  Task Switching "]);
+
+        assert!(filters.is_excluded("Program Manager", "explorer"));
+        assert!(filters.is_excluded("PROGRAM MANAGER (2)", "explorer"));
+        assert!(filters.is_excluded("Task Switching", "explorer"));
+        assert!(!filters.is_excluded("The Program Manager", "explorer"));
+    }
+
+    #[test]
+    fn excludes_processes_with_or_without_their_extension()
+    {
+        let filters = excluding(&["Discord", "steamwebhelper.EXE"]);
+
+        assert!(filters.is_excluded("Friends", "discord"));
+        assert!(filters.is_excluded("Steam", "steamwebhelper"));
+        assert!(!filters.is_excluded("Steam", "steam"));
+        assert!(!filters.is_excluded("Overlay", "DiscordOverlay"));
+    }
+
+    #[test]
+    fn ignores_empty_patterns()
+    {
+        assert!(!excluding(&[]).is_excluded("Notepad", "notepad"));
+        assert!(!excluding(&["", "   "]).is_excluded("Notepad", "notepad"));
+        assert!(!excluding(&[""]).is_excluded("", ""));
+    }
+
+    #[test]
+    fn skip_keeps_the_existing_profile()
+    {
+        let mut config = config_with(&[profile("Game", "old")]);
+        let report = add(
+            &mut config,
+            &[profile("game", "new"), profile("Other", "other")],
+            ConflictPolicy::Skip,
+        );
+
+        assert_eq!(report.imported, ["Other"]);
+        assert_eq!(report.warnings, ["game: a profile with this name already exists and was kept"]);
+        assert_eq!(names(&config), [("Game", Some("old")), ("Other", Some("other"))]);
+    }
+
+    #[test]
+    fn rename_picks_the_first_free_suffix()
+    {
+        let mut config = config_with(&[profile("Game", "old"), profile("Game (2)", "second")]);
+        let report = add(&mut config, &[profile("Game", "new")], ConflictPolicy::Rename);
+
+        assert_eq!(report.imported, ["Game (3)"]);
+        assert!(report.warnings.is_empty());
+        assert_eq!(
+            names(&config),
+            [("Game", Some("old")), ("Game (2)", Some("second")), ("Game (3)", Some("new"))]
+        );
+    }
+
+    #[test]
+    fn rename_also_separates_duplicates_within_one_import()
+    {
+        let mut config = Config::default();
+        let report = add(
+            &mut config,
+            &[profile("Game", "first"), profile("Game", "second")],
+            ConflictPolicy::Rename,
+        );
+
+        assert_eq!(report.imported, ["Game", "Game (2)"]);
+    }
+
+    #[test]
+    fn overwrite_replaces_the_existing_profile()
+    {
+        let mut config = config_with(&[profile("Game", "old"), profile("Other", "other")]);
+        let report = add(&mut config, &[profile("GAME", "new")], ConflictPolicy::Overwrite);
+
+        assert_eq!(report.imported, ["GAME"]);
+        assert!(report.warnings.is_empty());
+        assert_eq!(names(&config), [("Other", Some("other")), ("GAME", Some("new"))]);
+    }
+}
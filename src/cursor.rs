@@ -0,0 +1,150 @@
+use std::{
+    collections::HashSet,
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+use windows::Win32::{
+    Foundation::{HWND, POINT, RECT},
+    Graphics::Gdi::ClientToScreen,
+    UI::WindowsAndMessaging::{
+        ClipCursor, GetClientRect, GetClipCursor, GetForegroundWindow, IsIconic, IsWindow,
+    },
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+static STATE: LazyLock<Mutex<CursorState>> = LazyLock::new(|| Mutex::new(CursorState::default()));
+
+#[derive(Default)]
+struct CursorState
+{
+    locked: HashSet<isize>,
+    clip: Option<RECT>,
+    running: bool,
+}
+
+pub fn lock(hwnd: isize)
+{
+    let mut state = STATE.lock().unwrap();
+    state.locked.insert(hwnd);
+
+    if !state.running {
+        state.running = true;
+        std::thread::spawn(run);
+    }
+}
+
+pub fn unlock(hwnd: isize)
+{
+    let mut state = STATE.lock().unwrap();
+    if state.locked.remove(&hwnd) {
+        update(&mut state);
+    }
+}
+
+pub fn toggle(hwnd: isize) -> bool
+{
+    let locked = STATE.lock().unwrap().locked.contains(&hwnd);
+
+    if locked {
+        unlock(hwnd);
+    } else {
+        lock(hwnd);
+    }
+
+    !locked
+}
+
+pub fn release()
+{
+    let mut state = STATE.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    state.locked.clear();
+    update(&mut state);
+}
+
+fn run()
+{
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+
+        let mut state = STATE.lock().unwrap();
+        update(&mut state);
+
+        if state.locked.is_empty() && state.clip.is_none() {
+            state.running = false;
+            break;
+        }
+    }
+}
+
+fn update(state: &mut CursorState)
+{
+    state.locked.retain(|hwnd| unsafe { IsWindow(Some(to_hwnd(*hwnd))) }.as_bool());
+
+    let foreground = unsafe { GetForegroundWindow() }.0 as isize;
+    let target = if state.locked.contains(&foreground) { client_rect(foreground) } else { None };
+
+    match target {
+        Some(rect) => {
+            // Windows drops the clip on focus changes; a game's own tighter clip is kept.
+            let mut current = RECT::default();
+            let inside =
+                unsafe { GetClipCursor(&mut current) }.is_ok() && contains(&rect, &current);
+
+            if !inside {
+                unsafe {
+                    let _ = ClipCursor(Some(&rect));
+                }
+            }
+            state.clip = Some(rect);
+        },
+        None => {
+            if state.clip.take().is_some() {
+                unsafe {
+                    let _ = ClipCursor(None);
+                }
+            }
+        },
+    }
+}
+
+fn client_rect(hwnd: isize) -> Option<RECT>
+{
+    let handle = to_hwnd(hwnd);
+
+    unsafe {
+        if IsIconic(handle).as_bool() {
+            return None;
+        }
+
+        let mut rect = RECT::default();
+        GetClientRect(handle, &mut rect).ok()?;
+
+        let mut origin = POINT { x: 0, y: 0 };
+        if !ClientToScreen(handle, &mut origin).as_bool() {
+            return None;
+        }
+
+        let rect = RECT {
+            left: origin.x,
+            top: origin.y,
+            right: origin.x + rect.right,
+            bottom: origin.y + rect.bottom,
+        };
+
+        (rect.right > rect.left && rect.bottom > rect.top).then_some(rect)
+    }
+}
+
+fn contains(outer: &RECT, inner: &RECT) -> bool
+{
+    inner.left >= outer.left
+        && inner.top >= outer.top
+        && inner.right <= outer.right
+        && inner.bottom <= outer.bottom
+}
+
+fn to_hwnd(hwnd: isize) -> HWND
+{
+    HWND(hwnd as *mut std::ffi::c_void)
+}
@@ -0,0 +1,243 @@
+use crate::window_manager::{BorderlessEffects, FrameMode, WindowModifiers};
+use std::cell::RefCell;
+use windows::Win32::Foundation::RECT;
+
+const MAX_ENTRIES: usize = 50;
+
+thread_local! {
+    static TRANSACTION: RefCell<Option<Transaction>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowState
+{
+    pub frame: Option<FrameMode>,
+    pub effects: BorderlessEffects,
+    pub rect: RECT,
+    pub modifiers: WindowModifiers,
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryChange
+{
+    pub hwnd: isize,
+    pub title: String,
+    pub before: WindowState,
+    pub after: WindowState,
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryEntry
+{
+    pub label: String,
+    pub changes: Vec<HistoryChange>,
+}
+
+#[derive(Debug, Default)]
+pub struct History
+{
+    undo: Vec<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+}
+
+impl History
+{
+    pub fn push(&mut self, entry: HistoryEntry)
+    {
+        self.push_undo(entry);
+        self.redo.clear();
+    }
+
+    pub fn push_undo(&mut self, entry: HistoryEntry)
+    {
+        self.undo.push(entry);
+        if self.undo.len() > MAX_ENTRIES {
+            self.undo.remove(0);
+        }
+    }
+
+    pub fn push_redo(&mut self, entry: HistoryEntry)
+    {
+        self.redo.push(entry);
+    }
+
+    pub fn pop_undo(&mut self) -> Option<HistoryEntry>
+    {
+        self.undo.pop()
+    }
+
+    pub fn pop_redo(&mut self) -> Option<HistoryEntry>
+    {
+        self.redo.pop()
+    }
+
+    pub fn undo_entries(&self) -> &[HistoryEntry]
+    {
+        &self.undo
+    }
+
+    pub fn redo_entries(&self) -> &[HistoryEntry]
+    {
+        &self.redo
+    }
+}
+
+struct Transaction
+{
+    depth: u32,
+    record: bool,
+    before: Vec<(isize, WindowState)>,
+}
+
+// Operations nest (a profile toggles borders and modifiers, a swap moves two windows), so only
+// the outermost one on a thread collects the windows it touched into a single entry.
+pub fn begin(record: bool)
+{
+    TRANSACTION.with_borrow_mut(|transaction| match transaction {
+        Some(transaction) => transaction.depth += 1,
+        None => *transaction = Some(Transaction { depth: 1, record, before: Vec::new() }),
+    });
+}
+
+pub fn touch(hwnd: isize, snapshot: impl FnOnce() -> Option<WindowState>)
+{
+    TRANSACTION.with_borrow_mut(|transaction| {
+        if let Some(transaction) = transaction
+            && transaction.record
+            && !transaction.before.iter().any(|(touched, _)| *touched == hwnd)
+            && let Some(state) = snapshot()
+        {
+            transaction.before.push((hwnd, state));
+        }
+    });
+}
+
+// An `end` without a matching `begin` (or one more than was begun) finds no transaction and is a
+// no-op, so an unbalanced caller can never close or record someone else's entry.
+pub fn end() -> Option<Vec<(isize, WindowState)>>
+{
+    TRANSACTION.with_borrow_mut(|current| {
+        let transaction = current.as_mut()?;
+        transaction.depth = transaction.depth.saturating_sub(1);
+
+        if transaction.depth > 0 {
+            return None;
+        }
+
+        current
+            .take()
+            .filter(|transaction| transaction.record)
+            .map(|transaction| transaction.before)
+    })
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn state(left: i32) -> WindowState
+    {
+        WindowState {
+            frame: None,
+            effects: BorderlessEffects::default(),
+            rect: RECT { left, top: 0, right: left + 100, bottom: 100 },
+            modifiers: WindowModifiers::default(),
+        }
+    }
+
+    fn entry(label: &str) -> HistoryEntry
+    {
+        HistoryEntry {
+            label: label.to_string(),
+            changes: vec![HistoryChange {
+                hwnd: 1,
+                title: label.to_string(),
+                before: state(0),
+                after: state(10),
+            }],
+        }
+    }
+
+    fn labels(entries: &[HistoryEntry]) -> Vec<&str>
+    {
+        entries.iter().map(|entry| entry.label.as_str()).collect()
+    }
+
+    #[test]
+    fn nested_touches_coalesce_into_one_entry()
+    {
+        begin(true);
+        touch(1, || Some(state(0)));
+        begin(true);
+        touch(2, || Some(state(20)));
+        touch(1, || Some(state(99)));
+        assert_eq!(end(), None);
+
+        assert_eq!(end(), Some(vec![(1, state(0)), (2, state(20))]));
+    }
+
+    #[test]
+    fn replays_do_not_record_nested_operations()
+    {
+        begin(false);
+        begin(true);
+        touch(1, || Some(state(0)));
+        assert_eq!(end(), None);
+
+        assert_eq!(end(), None);
+    }
+
+    #[test]
+    fn end_without_begin_is_a_noop()
+    {
+        assert_eq!(end(), None);
+
+        begin(true);
+        touch(1, || Some(state(0)));
+        assert_eq!(end(), Some(vec![(1, state(0))]));
+        assert_eq!(end(), None);
+    }
+
+    #[test]
+    fn touch_outside_a_transaction_takes_no_snapshot()
+    {
+        touch(1, || panic!("no snapshot should be taken"));
+    }
+
+    #[test]
+    fn oldest_entries_drop_past_the_limit()
+    {
+        let mut history = History::default();
+        for index in 0..MAX_ENTRIES + 2 {
+            history.push(entry(&index.to_string()));
+        }
+
+        let entries = labels(history.undo_entries());
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries.first(), Some(&"2"));
+        assert_eq!(entries.last(), Some(&(MAX_ENTRIES + 1).to_string().as_str()));
+    }
+
+    #[test]
+    fn new_actions_clear_redo()
+    {
+        let mut history = History::default();
+        history.push(entry("first"));
+        history.push(entry("second"));
+
+        let undone = history.pop_undo().unwrap();
+        history.push_redo(undone);
+        assert_eq!(labels(history.redo_entries()), ["second"]);
+
+        let redone = history.pop_redo().unwrap();
+        history.push_undo(redone);
+        assert_eq!(labels(history.undo_entries()), ["first", "second"]);
+
+        let undone = history.pop_undo().unwrap();
+        history.push_redo(undone);
+        history.push(entry("third"));
+        assert!(history.redo_entries().is_empty());
+        assert_eq!(labels(history.undo_entries()), ["first", "third"]);
+    }
+}
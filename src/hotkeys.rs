@@ -0,0 +1,612 @@
+use crate::{
+    config::Config,
+    cursor, instance, logging,
+    window_manager::{self, WindowManager},
+};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{Arc, Mutex},
+    thread::JoinHandle,
+};
+use windows::Win32::{
+    Foundation::{LPARAM, WPARAM},
+    System::Threading::GetCurrentThreadId,
+    UI::{
+        Input::KeyboardAndMouse::{
+            GetAsyncKeyState, HOT_KEY_MODIFIERS, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, MOD_SHIFT,
+            MOD_WIN, RegisterHotKey, UnregisterHotKey, VK_BACK, VK_DELETE, VK_DOWN, VK_END,
+            VK_ESCAPE, VK_F1, VK_HOME, VK_INSERT, VK_LEFT, VK_LWIN, VK_NEXT, VK_OEM_1, VK_OEM_2,
+            VK_OEM_3, VK_OEM_4, VK_OEM_5, VK_OEM_6, VK_OEM_7, VK_OEM_COMMA, VK_OEM_MINUS,
+            VK_OEM_PERIOD, VK_OEM_PLUS, VK_PRIOR, VK_RETURN, VK_RIGHT, VK_RWIN, VK_SPACE, VK_TAB,
+            VK_UP,
+        },
+        WindowsAndMessaging::{
+            GetMessageW, MSG, PM_NOREMOVE, PeekMessageW, PostThreadMessageW, WM_APP, WM_HOTKEY,
+            WM_QUIT,
+        },
+    },
+};
+
+const WM_RELOAD_HOTKEYS: u32 = WM_APP + 2;
+const WM_SUSPEND_HOTKEYS: u32 = WM_APP + 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyAction
+{
+    ToggleBorderless,
+    NextDisplay,
+    Display1,
+    Display2,
+    Display3,
+    SwapDisplays,
+    LockCursor,
+    RestoreAll,
+    ShowWindow,
+}
+
+impl HotkeyAction
+{
+    pub const ALL: [Self; 9] = [
+        Self::ToggleBorderless,
+        Self::NextDisplay,
+        Self::Display1,
+        Self::Display2,
+        Self::Display3,
+        Self::SwapDisplays,
+        Self::LockCursor,
+        Self::RestoreAll,
+        Self::ShowWindow,
+    ];
+
+    pub fn label(self) -> &'static str
+    {
+        match self {
+            Self::ToggleBorderless => "Toggle focused window",
+            Self::NextDisplay => "Move to next display",
+            Self::Display1 => "Move to display 1",
+            Self::Display2 => "Move to display 2",
+            Self::Display3 => "Move to display 3",
+            Self::SwapDisplays => "Swap with window on next display",
+            Self::LockCursor => "Lock cursor to focused window",
+            Self::RestoreAll => "Restore all windows",
+            Self::ShowWindow => "Open ihateborders",
+        }
+    }
+
+    fn display_index(self) -> usize
+    {
+        match self {
+            Self::Display2 => 1,
+            Self::Display3 => 2,
+            _ => 0,
+        }
+    }
+
+    fn id(self) -> i32
+    {
+        Self::ALL.iter().position(|action| *action == self).unwrap_or(0) as i32 + 1
+    }
+
+    fn from_id(id: i32) -> Option<Self>
+    {
+        Self::ALL.get((id - 1) as usize).copied()
+    }
+}
+
+pub fn default_bindings() -> BTreeMap<HotkeyAction, String>
+{
+    BTreeMap::from([
+        (HotkeyAction::ToggleBorderless, "Ctrl+Alt+B".to_string()),
+        (HotkeyAction::NextDisplay, "Ctrl+Alt+N".to_string()),
+        (HotkeyAction::Display1, "Ctrl+Alt+1".to_string()),
+        (HotkeyAction::Display2, "Ctrl+Alt+2".to_string()),
+        (HotkeyAction::Display3, "Ctrl+Alt+3".to_string()),
+        (HotkeyAction::SwapDisplays, "Ctrl+Alt+S".to_string()),
+        (HotkeyAction::LockCursor, "Ctrl+Alt+L".to_string()),
+        (HotkeyAction::RestoreAll, "Ctrl+Alt+R".to_string()),
+        (HotkeyAction::ShowWindow, "Ctrl+Alt+I".to_string()),
+    ])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hotkey
+{
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub win: bool,
+    pub key: egui::Key,
+}
+
+impl Hotkey
+{
+    pub fn parse(text: &str) -> anyhow::Result<Self>
+    {
+        let mut hotkey = None;
+        let (mut ctrl, mut alt, mut shift, mut win) = (false, false, false, false);
+
+        for part in text.split('+').map(str::trim) {
+            match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => ctrl = true,
+                "alt" => alt = true,
+                "shift" => shift = true,
+                "win" | "super" | "meta" => win = true,
+                "" => anyhow::bail!("Empty key in '{}'", text),
+                _ => {
+                    if hotkey.is_some() {
+                        anyhow::bail!("More than one key in '{}'", text);
+                    }
+                    let key = egui::Key::from_name(part)
+                        .ok_or_else(|| anyhow::anyhow!("Unknown key '{}'", part))?;
+                    hotkey = Some(key);
+                },
+            }
+        }
+
+        let key = hotkey.ok_or_else(|| anyhow::anyhow!("No key in '{}'", text))?;
+        let hotkey = Self { ctrl, alt, shift, win, key };
+
+        if virtual_key(key).is_none() {
+            anyhow::bail!("Key '{}' cannot be used as a global hotkey", key.name());
+        }
+
+        Ok(hotkey)
+    }
+
+    // egui has no Windows-key modifier, so it is read from the keyboard when a key is captured.
+    pub fn from_egui(modifiers: egui::Modifiers, key: egui::Key) -> Self
+    {
+        let win = [VK_LWIN, VK_RWIN].iter().any(|vk| unsafe { GetAsyncKeyState(vk.0 as i32) } < 0);
+
+        Self { ctrl: modifiers.ctrl, alt: modifiers.alt, shift: modifiers.shift, win, key }
+    }
+
+    fn binding(&self) -> (HOT_KEY_MODIFIERS, u32)
+    {
+        (self.modifiers(), virtual_key(self.key).unwrap_or(0))
+    }
+
+    fn modifiers(&self) -> HOT_KEY_MODIFIERS
+    {
+        let mut modifiers = MOD_NOREPEAT;
+        if self.ctrl {
+            modifiers |= MOD_CONTROL;
+        }
+        if self.alt {
+            modifiers |= MOD_ALT;
+        }
+        if self.shift {
+            modifiers |= MOD_SHIFT;
+        }
+        if self.win {
+            modifiers |= MOD_WIN;
+        }
+        modifiers
+    }
+}
+
+impl fmt::Display for Hotkey
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.win {
+            write!(f, "Win+")?;
+        }
+        write!(f, "{}", self.key.name())
+    }
+}
+
+fn virtual_key(key: egui::Key) -> Option<u32>
+{
+    let name = key.name();
+
+    if name.len() == 1 && name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) {
+        return Some(name.as_bytes()[0] as u32);
+    }
+
+    if let Some(number) = name.strip_prefix('F').and_then(|n| n.parse::<u32>().ok()) {
+        return (1..=24).contains(&number).then(|| VK_F1.0 as u32 + number - 1);
+    }
+
+    let vk = match key {
+        egui::Key::ArrowDown => VK_DOWN,
+        egui::Key::ArrowLeft => VK_LEFT,
+        egui::Key::ArrowRight => VK_RIGHT,
+        egui::Key::ArrowUp => VK_UP,
+        egui::Key::Escape => VK_ESCAPE,
+        egui::Key::Tab => VK_TAB,
+        egui::Key::Backspace => VK_BACK,
+        egui::Key::Enter => VK_RETURN,
+        egui::Key::Space => VK_SPACE,
+        egui::Key::Insert => VK_INSERT,
+        egui::Key::Delete => VK_DELETE,
+        egui::Key::Home => VK_HOME,
+        egui::Key::End => VK_END,
+        egui::Key::PageUp => VK_PRIOR,
+        egui::Key::PageDown => VK_NEXT,
+        egui::Key::Minus => VK_OEM_MINUS,
+        egui::Key::Plus | egui::Key::Equals => VK_OEM_PLUS,
+        egui::Key::Comma => VK_OEM_COMMA,
+        egui::Key::Period => VK_OEM_PERIOD,
+        egui::Key::Semicolon => VK_OEM_1,
+        egui::Key::Slash => VK_OEM_2,
+        egui::Key::Backtick => VK_OEM_3,
+        egui::Key::OpenBracket => VK_OEM_4,
+        egui::Key::Backslash => VK_OEM_5,
+        egui::Key::CloseBracket => VK_OEM_6,
+        egui::Key::Quote => VK_OEM_7,
+        _ => return None,
+    };
+
+    Some(vk.0 as u32)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HotkeyStatus
+{
+    Unbound,
+    Registered,
+    Invalid(String),
+    Conflict(String),
+}
+
+pub struct HotkeyService
+{
+    thread_id: u32,
+    thread: Option<JoinHandle<()>>,
+    statuses: Arc<Mutex<BTreeMap<HotkeyAction, HotkeyStatus>>>,
+}
+
+impl HotkeyService
+{
+    pub fn start(
+        window_manager: WindowManager,
+        config: Arc<Mutex<Config>>,
+        egui_ctx: egui::Context,
+    ) -> Self
+    {
+        let statuses = Arc::new(Mutex::new(BTreeMap::new()));
+        let thread_statuses = Arc::clone(&statuses);
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        let thread = std::thread::spawn(move || unsafe {
+            let mut msg = MSG::default();
+            let _ = PeekMessageW(&mut msg, None, 0, 0, PM_NOREMOVE);
+            let _ = sender.send(GetCurrentThreadId());
+
+            register_hotkeys(&config, &thread_statuses);
+            egui_ctx.request_repaint();
+
+            while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+                match msg.message {
+                    WM_HOTKEY => {
+                        if let Some(action) = HotkeyAction::from_id(msg.wParam.0 as i32) {
+                            run_action(action, &window_manager, &config, &egui_ctx);
+                        }
+                    },
+                    WM_RELOAD_HOTKEYS => {
+                        unregister_hotkeys();
+                        register_hotkeys(&config, &thread_statuses);
+                        egui_ctx.request_repaint();
+                    },
+                    WM_SUSPEND_HOTKEYS => unregister_hotkeys(),
+                    _ => {},
+                }
+            }
+
+            unregister_hotkeys();
+        });
+
+        let thread_id = receiver.recv().unwrap_or(0);
+
+        Self { thread_id, thread: Some(thread), statuses }
+    }
+
+    pub fn reload(&self)
+    {
+        unsafe {
+            let _ = PostThreadMessageW(self.thread_id, WM_RELOAD_HOTKEYS, WPARAM(0), LPARAM(0));
+        }
+    }
+
+    pub fn suspend(&self)
+    {
+        unsafe {
+            let _ = PostThreadMessageW(self.thread_id, WM_SUSPEND_HOTKEYS, WPARAM(0), LPARAM(0));
+        }
+    }
+
+    pub fn statuses(&self) -> BTreeMap<HotkeyAction, HotkeyStatus>
+    {
+        self.statuses.lock().unwrap().clone()
+    }
+}
+
+impl Drop for HotkeyService
+{
+    fn drop(&mut self)
+    {
+        unsafe {
+            let _ = PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+        }
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn register_hotkeys(
+    config: &Arc<Mutex<Config>>,
+    statuses: &Arc<Mutex<BTreeMap<HotkeyAction, HotkeyStatus>>>,
+)
+{
+    let bindings = config.lock().unwrap().hotkeys.clone();
+    let mut results = BTreeMap::new();
+
+    for (action, planned) in plan_hotkeys(&bindings) {
+        let status = match planned {
+            Err(status) => status,
+            Ok(hotkey) => {
+                let (modifiers, vk) = hotkey.binding();
+                match unsafe { RegisterHotKey(None, action.id(), modifiers, vk) } {
+                    Ok(()) => HotkeyStatus::Registered,
+                    Err(_) => {
+                        HotkeyStatus::Conflict("Already in use by another application".to_string())
+                    },
+                }
+            },
+        };
+
+        results.insert(action, status);
+    }
+
+    *statuses.lock().unwrap() = results;
+}
+
+fn plan_hotkeys(
+    bindings: &BTreeMap<HotkeyAction, String>,
+) -> Vec<(HotkeyAction, Result<Hotkey, HotkeyStatus>)>
+{
+    let mut planned: Vec<(HotkeyAction, Result<Hotkey, HotkeyStatus>)> = Vec::new();
+
+    for action in HotkeyAction::ALL {
+        let result = match bindings.get(&action).map(|text| text.trim()) {
+            None | Some("") => Err(HotkeyStatus::Unbound),
+            Some(text) => match Hotkey::parse(text) {
+                Err(e) => Err(HotkeyStatus::Invalid(e.to_string())),
+                Ok(hotkey) => {
+                    let other = planned.iter().find(|(_, used)| {
+                        used.as_ref().is_ok_and(|used| used.binding() == hotkey.binding())
+                    });
+                    match other {
+                        Some((other, _)) => Err(HotkeyStatus::Conflict(format!(
+                            "Also bound to '{}'",
+                            other.label()
+                        ))),
+                        None => Ok(hotkey),
+                    }
+                },
+            },
+        };
+
+        planned.push((action, result));
+    }
+
+    planned
+}
+
+fn unregister_hotkeys()
+{
+    for action in HotkeyAction::ALL {
+        unsafe {
+            let _ = UnregisterHotKey(None, action.id());
+        }
+    }
+}
+
+fn run_action(
+    action: HotkeyAction,
+    window_manager: &WindowManager,
+    config: &Arc<Mutex<Config>>,
+    egui_ctx: &egui::Context,
+)
+{
+    logging::info("hotkey", format!("Hotkey '{}' pressed", action.label()));
+
+    let result = match action {
+        HotkeyAction::ToggleBorderless => match window_manager::foreground_window() {
+            Some(hwnd) => {
+                let window =
+                    window_manager.get_windows().into_iter().find(|window| window.hwnd == hwnd);
+                let ((resize_to_screen, display, frame_mode), effects) = {
+                    let config = config.lock().unwrap();
+                    (config.placement_for(window.as_ref()), config.effects())
+                };
+                let displays = window_manager.get_displays();
+                let selected_display = if resize_to_screen {
+                    window_manager::find_display(&displays, display.as_deref())
+                } else {
+                    None
+                };
+
+                window_manager.toggle_borderless(
+                    hwnd,
+                    resize_to_screen,
+                    selected_display,
+                    frame_mode,
+                    effects,
+                )
+            },
+            None => Ok(()),
+        },
+        HotkeyAction::NextDisplay => match window_manager::foreground_window() {
+            Some(hwnd) => window_manager.move_to_next_display(hwnd),
+            None => Ok(()),
+        },
+        HotkeyAction::Display1 | HotkeyAction::Display2 | HotkeyAction::Display3 => {
+            match window_manager::foreground_window() {
+                Some(hwnd) => window_manager.send_to_display(hwnd, action.display_index()),
+                None => Ok(()),
+            }
+        },
+        HotkeyAction::SwapDisplays => match window_manager::foreground_window() {
+            Some(hwnd) => window_manager.swap_displays(hwnd, None).map(|_| ()),
+            None => Ok(()),
+        },
+        HotkeyAction::LockCursor => {
+            if let Some(hwnd) = window_manager::foreground_window() {
+                cursor::toggle(hwnd);
+            }
+            Ok(())
+        },
+        HotkeyAction::RestoreAll => window_manager.restore_all(),
+        HotkeyAction::ShowWindow => {
+            instance::show_main_window(egui_ctx);
+            Ok(())
+        },
+    };
+
+    if let Err(e) = result {
+        logging::warn("hotkey", format!("Hotkey '{}' failed: {}", action.label(), e));
+    }
+
+    egui_ctx.request_repaint();
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn statuses(bindings: &[(HotkeyAction, &str)]) -> BTreeMap<HotkeyAction, HotkeyStatus>
+    {
+        let bindings = bindings.iter().map(|(action, text)| (*action, text.to_string())).collect();
+
+        plan_hotkeys(&bindings)
+            .into_iter()
+            .map(|(action, planned)| {
+                (action, planned.map_or_else(|status| status, |_| HotkeyStatus::Registered))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_modifiers_in_any_order_and_case()
+    {
+        let hotkey = Hotkey::parse("shift + CONTROL+alt+win+b").unwrap();
+
+        assert_eq!(
+            hotkey,
+            Hotkey { ctrl: true, alt: true, shift: true, win: true, key: egui::Key::B }
+        );
+        assert_eq!(hotkey.to_string(), "Ctrl+Alt+Shift+Win+B");
+        assert_eq!(Hotkey::parse(&hotkey.to_string()).unwrap(), hotkey);
+    }
+
+    #[test]
+    fn parses_function_and_punctuation_keys()
+    {
+        let (modifiers, vk) = Hotkey::parse("Ctrl+F12").unwrap().binding();
+        assert_eq!(modifiers, MOD_NOREPEAT | MOD_CONTROL);
+        assert_eq!(vk, VK_F1.0 as u32 + 11);
+
+        assert_eq!(Hotkey::parse("Alt+Minus").unwrap().binding().1, VK_OEM_MINUS.0 as u32);
+        assert_eq!(Hotkey::parse("Alt+7").unwrap().binding().1, u32::from(b'7'));
+    }
+
+    #[test]
+    fn rejects_malformed_hotkeys()
+    {
+        for (text, message) in [
+            ("Ctrl+Alt", "No key in 'Ctrl+Alt'"),
+            ("Ctrl++B", "Empty key in 'Ctrl++B'"),
+            ("Ctrl+A+B", "More than one key in 'Ctrl+A+B'"),
+            ("Ctrl+Hyper", "Unknown key 'Hyper'"),
+            ("Ctrl+F25", "Key 'F25' cannot be used as a global hotkey"),
+        ] {
+            assert_eq!(Hotkey::parse(text).unwrap_err().to_string(), message);
+        }
+    }
+
+    #[test]
+    fn keys_sharing_a_virtual_key_conflict()
+    {
+        let statuses = statuses(&[
+            (HotkeyAction::ToggleBorderless, "Ctrl+Alt+Plus"),
+            (HotkeyAction::NextDisplay, "Ctrl+Alt+Equals"),
+        ]);
+
+        assert_eq!(statuses[&HotkeyAction::ToggleBorderless], HotkeyStatus::Registered);
+        assert_eq!(
+            statuses[&HotkeyAction::NextDisplay],
+            HotkeyStatus::Conflict("Also bound to 'Toggle focused window'".to_string())
+        );
+    }
+
+    #[test]
+    fn the_same_binding_written_differently_conflicts()
+    {
+        let statuses = statuses(&[
+            (HotkeyAction::Display1, "ctrl+alt+b"),
+            (HotkeyAction::RestoreAll, "Alt + Ctrl + B"),
+        ]);
+
+        assert_eq!(statuses[&HotkeyAction::Display1], HotkeyStatus::Registered);
+        assert_eq!(
+            statuses[&HotkeyAction::RestoreAll],
+            HotkeyStatus::Conflict("Also bound to 'Move to display 1'".to_string())
+        );
+    }
+
+    #[test]
+    fn different_modifiers_do_not_conflict()
+    {
+        let statuses = statuses(&[
+            (HotkeyAction::ToggleBorderless, "Ctrl+Alt+B"),
+            (HotkeyAction::NextDisplay, "Ctrl+Shift+B"),
+        ]);
+
+        assert_eq!(statuses[&HotkeyAction::ToggleBorderless], HotkeyStatus::Registered);
+        assert_eq!(statuses[&HotkeyAction::NextDisplay], HotkeyStatus::Registered);
+    }
+
+    #[test]
+    fn invalid_and_unbound_keys_do_not_block_others()
+    {
+        let statuses = statuses(&[
+            (HotkeyAction::ToggleBorderless, "Ctrl+Hyper"),
+            (HotkeyAction::NextDisplay, "  "),
+            (HotkeyAction::SwapDisplays, "Ctrl+Alt+S"),
+        ]);
+
+        assert_eq!(
+            statuses[&HotkeyAction::ToggleBorderless],
+            HotkeyStatus::Invalid("Unknown key 'Hyper'".to_string())
+        );
+        assert_eq!(statuses[&HotkeyAction::NextDisplay], HotkeyStatus::Unbound);
+        assert_eq!(statuses[&HotkeyAction::Display1], HotkeyStatus::Unbound);
+        assert_eq!(statuses[&HotkeyAction::SwapDisplays], HotkeyStatus::Registered);
+    }
+
+    #[test]
+    fn default_bindings_do_not_conflict()
+    {
+        let statuses = plan_hotkeys(&default_bindings());
+
+        assert!(statuses.iter().all(|(_, planned)| planned.is_ok()));
+    }
+}
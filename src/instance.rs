@@ -0,0 +1,184 @@
+use crate::{cli::Command, ipc, logging};
+use eframe::egui;
+use serde_json::{Value, json};
+use std::time::Duration;
+use windows::{
+    Win32::{
+        Foundation::{CloseHandle, ERROR_ALREADY_EXISTS, GetLastError, HANDLE, HWND},
+        System::Threading::{
+            CreateMutexW, GetCurrentProcessId, OpenProcess, PROCESS_SYNCHRONIZE, ReleaseMutex,
+            WaitForSingleObject,
+        },
+        UI::{
+            Shell::ShellExecuteW,
+            WindowsAndMessaging::{
+                ASFW_ANY, AllowSetForegroundWindow, FindWindowExW, GetWindowThreadProcessId,
+                IsIconic, SW_HIDE, SW_RESTORE, SW_SHOW, SW_SHOWNORMAL, SetForegroundWindow,
+                ShowWindow,
+            },
+        },
+    },
+    core::{HSTRING, PCWSTR, w},
+};
+
+pub struct InstanceLock(HANDLE);
+
+impl InstanceLock
+{
+    pub fn acquire() -> windows::core::Result<Option<Self>>
+    {
+        unsafe {
+            let handle = CreateMutexW(None, true, w!("Local\\ihateborders"))?;
+
+            if GetLastError() == ERROR_ALREADY_EXISTS {
+                let _ = CloseHandle(handle);
+                return Ok(None);
+            }
+
+            Ok(Some(Self(handle)))
+        }
+    }
+}
+
+impl Drop for InstanceLock
+{
+    fn drop(&mut self)
+    {
+        unsafe {
+            let _ = ReleaseMutex(self.0);
+            let _ = CloseHandle(self.0);
+        }
+    }
+}
+
+pub fn forward(command: &Command) -> anyhow::Result<()>
+{
+    unsafe {
+        let _ = AllowSetForegroundWindow(ASFW_ANY);
+    }
+
+    let mut client = connect_with_retry()?;
+
+    match command {
+        Command::Show => client.call("show_window", Value::Null)?,
+        Command::ApplyProfile(name) => client.call("apply_profile", json!({ "name": name }))?,
+        Command::Run(options) => client.call("run", serde_json::to_value(options)?)?,
+        Command::ImportBorderlessGaming(path) => {
+            print_warnings(client.call("import_borderless_gaming", json!({ "path": path }))?)
+        },
+        Command::ExportProfiles { path, names } => {
+            client.call("export_profiles", json!({ "path": path, "names": names }))?
+        },
+        Command::ImportProfiles { path, on_conflict } => print_warnings(
+            client.call("import_profiles", json!({ "path": path, "on_conflict": on_conflict }))?,
+        ),
+        Command::Relaunch { .. } => client.call("show_window", Value::Null)?,
+    };
+
+    Ok(())
+}
+
+pub fn relaunch_elevated(select: Option<isize>) -> anyhow::Result<()>
+{
+    let exe = std::env::current_exe()?;
+    let mut parameters = format!("relaunch --after {}", unsafe { GetCurrentProcessId() });
+    if let Some(hwnd) = select {
+        parameters.push_str(&format!(" --select {}", hwnd));
+    }
+
+    let result = unsafe {
+        ShellExecuteW(
+            None,
+            w!("runas"),
+            &HSTRING::from(exe.as_os_str()),
+            &HSTRING::from(parameters),
+            PCWSTR::null(),
+            SW_SHOWNORMAL,
+        )
+    };
+
+    // ShellExecuteW reports failures, including a declined UAC prompt, as values up to 32.
+    if result.0 as isize <= 32 {
+        anyhow::bail!("Restarting as administrator was cancelled or failed");
+    }
+
+    Ok(())
+}
+
+pub fn wait_for_exit(process_id: u32)
+{
+    unsafe {
+        if let Ok(process) = OpenProcess(PROCESS_SYNCHRONIZE, false, process_id) {
+            let _ = WaitForSingleObject(process, 10_000);
+            let _ = CloseHandle(process);
+        }
+    }
+}
+
+fn print_warnings(report: Value) -> Value
+{
+    for warning in report["warnings"].as_array().into_iter().flatten() {
+        logging::warn("cli", warning.as_str().unwrap_or_default());
+    }
+
+    report
+}
+
+fn connect_with_retry() -> std::io::Result<ipc::Client>
+{
+    let mut attempts = 0;
+
+    loop {
+        match ipc::Client::connect() {
+            Ok(client) => return Ok(client),
+            Err(e) if attempts >= 20 => return Err(e),
+            Err(_) => {
+                attempts += 1;
+                std::thread::sleep(Duration::from_millis(250));
+            },
+        }
+    }
+}
+
+pub fn show_main_window(ctx: &egui::Context)
+{
+    if let Some(hwnd) = find_main_window() {
+        unsafe {
+            let _ = ShowWindow(hwnd, SW_SHOW);
+            if IsIconic(hwnd).as_bool() {
+                let _ = ShowWindow(hwnd, SW_RESTORE);
+            }
+            let _ = SetForegroundWindow(hwnd);
+        }
+    }
+
+    ctx.request_repaint();
+}
+
+pub fn hide_main_window()
+{
+    if let Some(hwnd) = find_main_window() {
+        unsafe {
+            let _ = ShowWindow(hwnd, SW_HIDE);
+        }
+    }
+}
+
+fn find_main_window() -> Option<HWND>
+{
+    unsafe {
+        let current_process = GetCurrentProcessId();
+        let mut after = None;
+
+        while let Ok(hwnd) = FindWindowExW(None, after, PCWSTR::null(), w!("ihateborders")) {
+            let mut process_id = 0u32;
+            GetWindowThreadProcessId(hwnd, Some(&mut process_id));
+            if process_id == current_process {
+                return Some(hwnd);
+            }
+            after = Some(hwnd);
+        }
+    }
+
+    None
+}
@@ -0,0 +1,923 @@
+use crate::{
+    borderless_gaming, bundle,
+    config::{Config, ConflictPolicy},
+    instance,
+    launcher::{self, RunOptions},
+    logging,
+    window_manager::{self, FrameMode, WindowManager, WindowSize},
+};
+use eframe::egui;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use std::{
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+const SERVER_NAME: &str = "ihateborders";
+
+#[derive(Debug, Deserialize)]
+struct Request
+{
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcError
+{
+    code: i64,
+    message: String,
+}
+
+impl RpcError
+{
+    fn parse_error(error: impl std::fmt::Display) -> Self
+    {
+        Self { code: -32700, message: format!("Parse error: {}", error) }
+    }
+
+    fn method_not_found(method: &str) -> Self
+    {
+        Self { code: -32601, message: format!("Method not found: {}", method) }
+    }
+
+    fn invalid_params(error: impl std::fmt::Display) -> Self
+    {
+        Self { code: -32602, message: format!("Invalid params: {}", error) }
+    }
+}
+
+impl From<anyhow::Error> for RpcError
+{
+    fn from(error: anyhow::Error) -> Self
+    {
+        Self { code: -32000, message: error.to_string() }
+    }
+}
+
+impl From<serde_json::Error> for RpcError
+{
+    fn from(error: serde_json::Error) -> Self
+    {
+        Self { code: -32603, message: format!("Internal error: {}", error) }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct HwndParams
+{
+    hwnd: isize,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetBorderlessParams
+{
+    hwnd: isize,
+    #[serde(default = "default_resize_to_screen")]
+    resize_to_screen: bool,
+    display: Option<String>,
+    frame_mode: Option<FrameMode>,
+    size: Option<WindowSize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MoveToDisplayParams
+{
+    hwnd: isize,
+    display: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SwapDisplaysParams
+{
+    hwnd: isize,
+    other: Option<isize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApplyProfileParams
+{
+    name: String,
+    hwnd: Option<isize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetModifiersParams
+{
+    hwnd: isize,
+    always_on_top: Option<bool>,
+    opacity: Option<u8>,
+    click_through: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportParams
+{
+    path: Option<String>,
+    on_conflict: Option<ConflictPolicy>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportParams
+{
+    path: String,
+    #[serde(default)]
+    names: Vec<String>,
+}
+
+fn default_resize_to_screen() -> bool
+{
+    true
+}
+
+#[derive(Clone)]
+struct ServerState
+{
+    window_manager: WindowManager,
+    config: Arc<Mutex<Config>>,
+    egui_ctx: egui::Context,
+}
+
+pub fn start_server(
+    window_manager: WindowManager,
+    config: Arc<Mutex<Config>>,
+    egui_ctx: egui::Context,
+)
+{
+    let state = ServerState { window_manager, config, egui_ctx };
+
+    match transport::Listener::bind(SERVER_NAME) {
+        Ok(listener) => {
+            std::thread::spawn(move || serve(listener, state));
+        },
+        Err(e) => logging::error("ipc", format!("Failed to start IPC server: {}", e)),
+    }
+}
+
+fn serve(listener: transport::Listener, state: ServerState)
+{
+    loop {
+        match listener.accept() {
+            Ok(stream) => {
+                let state = state.clone();
+                std::thread::spawn(move || handle_connection(stream, &state));
+            },
+            Err(e) => {
+                logging::warn("ipc", format!("Failed to accept IPC connection: {}", e));
+                std::thread::sleep(Duration::from_secs(1));
+            },
+        }
+    }
+}
+
+fn handle_connection(stream: transport::Stream, state: &ServerState)
+{
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) | Err(_) => return,
+            Ok(_) => {},
+        }
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let request = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                if write_message(
+                    reader.get_mut(),
+                    &error_response(Value::Null, RpcError::parse_error(e)),
+                )
+                .is_err()
+                {
+                    return;
+                }
+                continue;
+            },
+        };
+
+        if request.method == "subscribe" {
+            stream_events(reader.get_mut(), request.id, &state.window_manager);
+            return;
+        }
+
+        let response = match dispatch(&request.method, request.params, state) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": request.id, "result": result }),
+            Err(error) => error_response(request.id, error),
+        };
+
+        if write_message(reader.get_mut(), &response).is_err() {
+            return;
+        }
+    }
+}
+
+fn stream_events(stream: &mut transport::Stream, id: Value, window_manager: &WindowManager)
+{
+    let events = window_manager.subscribe();
+
+    if write_message(stream, &json!({ "jsonrpc": "2.0", "id": id, "result": true })).is_err() {
+        return;
+    }
+
+    for event in events {
+        let notification = json!({ "jsonrpc": "2.0", "method": "window_event", "params": event });
+        if write_message(stream, &notification).is_err() {
+            return;
+        }
+    }
+}
+
+fn dispatch(method: &str, params: Value, state: &ServerState) -> Result<Value, RpcError>
+{
+    let window_manager = &state.window_manager;
+
+    match method {
+        "list_windows" => {
+            window_manager.refresh_windows();
+            Ok(serde_json::to_value(window_manager.get_windows())?)
+        },
+        "list_displays" => Ok(serde_json::to_value(window_manager.get_displays())?),
+        "set_borderless" => {
+            let params: SetBorderlessParams = parse_params(params)?;
+            let displays = window_manager.get_displays();
+            let selected_display = if params.resize_to_screen {
+                window_manager::find_display(&displays, params.display.as_deref())
+            } else {
+                None
+            };
+
+            let (frame_mode, effects) = {
+                let config = state.config.lock().unwrap();
+                (params.frame_mode.unwrap_or(config.frame_mode), config.effects())
+            };
+
+            match params.size {
+                Some(size) => window_manager.set_borderless_sized(
+                    params.hwnd,
+                    params.resize_to_screen,
+                    selected_display,
+                    frame_mode,
+                    Some(size),
+                    effects,
+                )?,
+                None => window_manager.set_borderless(
+                    params.hwnd,
+                    params.resize_to_screen,
+                    selected_display,
+                    frame_mode,
+                    effects,
+                )?,
+            }
+            Ok(Value::Bool(true))
+        },
+        "restore" => {
+            let params: HwndParams = parse_params(params)?;
+            window_manager.restore_borders(params.hwnd)?;
+            Ok(Value::Bool(true))
+        },
+        "set_modifiers" => {
+            let params: SetModifiersParams = parse_params(params)?;
+            let mut modifiers = window_manager.modifiers(params.hwnd);
+            modifiers.always_on_top = params.always_on_top.unwrap_or(modifiers.always_on_top);
+            modifiers.opacity = params.opacity.unwrap_or(modifiers.opacity);
+            modifiers.click_through = params.click_through.unwrap_or(modifiers.click_through);
+
+            window_manager.set_modifiers(params.hwnd, modifiers)?;
+            state.egui_ctx.request_repaint();
+            Ok(Value::Bool(true))
+        },
+        "move_to_display" => {
+            let params: MoveToDisplayParams = parse_params(params)?;
+            match params.display {
+                Some(name) => {
+                    let displays = window_manager.get_displays();
+                    let index = displays
+                        .iter()
+                        .position(|display| display.name.eq_ignore_ascii_case(&name))
+                        .ok_or_else(|| anyhow::anyhow!("Unknown display '{}'", name))?;
+                    window_manager.send_to_display(params.hwnd, index)?;
+                },
+                None => window_manager.move_to_next_display(params.hwnd)?,
+            }
+            Ok(Value::Bool(true))
+        },
+        "swap_displays" => {
+            let params: SwapDisplaysParams = parse_params(params)?;
+            let other = window_manager.swap_displays(params.hwnd, params.other)?;
+            Ok(json!({ "other": other }))
+        },
+        "apply_profile" => {
+            let params: ApplyProfileParams = parse_params(params)?;
+            let profile = state
+                .config
+                .lock()
+                .unwrap()
+                .find_profile(&params.name)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Unknown profile '{}'", params.name))?;
+            let displays = window_manager.get_displays();
+
+            let targets = match params.hwnd {
+                Some(hwnd) => {
+                    window_manager.apply_profile(hwnd, &profile, &displays)?;
+                    vec![hwnd]
+                },
+                None => window_manager.apply_profile_to_matching(&profile, &displays)?,
+            };
+
+            Ok(serde_json::to_value(targets)?)
+        },
+        "run" => {
+            let params: RunOptions = parse_params(params)?;
+            let process_id = launcher::run(
+                &params,
+                window_manager.clone(),
+                &state.config,
+                state.egui_ctx.clone(),
+            )?;
+            Ok(json!({ "process_id": process_id }))
+        },
+        "import_borderless_gaming" => {
+            let params: ImportParams = parse_params(params)?;
+            let displays = window_manager.get_displays();
+            let mut config = state.config.lock().unwrap();
+            let policy = params.on_conflict.unwrap_or(config.import_conflict);
+            let report = borderless_gaming::import(
+                params.path.as_deref().map(Path::new),
+                &mut config,
+                &displays,
+                policy,
+            )?;
+            config.save()?;
+            state.egui_ctx.request_repaint();
+            Ok(serde_json::to_value(report)?)
+        },
+        "export_profiles" => {
+            let params: ExportParams = parse_params(params)?;
+            let profiles = state.config.lock().unwrap().profiles_named(&params.names)?;
+            bundle::export(Path::new(&params.path), &profiles, &window_manager.get_displays())?;
+            Ok(json!({ "exported": profiles.len() }))
+        },
+        "import_profiles" => {
+            let params: ImportParams = parse_params(params)?;
+            let path = params.path.ok_or_else(|| RpcError::invalid_params("missing path"))?;
+            let displays = window_manager.get_displays();
+            let mut config = state.config.lock().unwrap();
+            let policy = params.on_conflict.unwrap_or(config.import_conflict);
+            let report = bundle::import(Path::new(&path), &mut config, &displays, policy)?;
+            config.save()?;
+            state.egui_ctx.request_repaint();
+            Ok(serde_json::to_value(report)?)
+        },
+        "show_window" => {
+            instance::show_main_window(&state.egui_ctx);
+            Ok(Value::Bool(true))
+        },
+        _ => Err(RpcError::method_not_found(method)),
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError>
+{
+    serde_json::from_value(params).map_err(RpcError::invalid_params)
+}
+
+fn error_response(id: Value, error: RpcError) -> Value
+{
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+fn write_message(stream: &mut transport::Stream, message: &Value) -> std::io::Result<()>
+{
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+    stream.flush()
+}
+
+pub struct Client
+{
+    reader: BufReader<transport::Stream>,
+    next_id: u64,
+}
+
+impl Client
+{
+    pub fn connect() -> std::io::Result<Self>
+    {
+        Self::connect_to(SERVER_NAME)
+    }
+
+    fn connect_to(name: &str) -> std::io::Result<Self>
+    {
+        Ok(Self { reader: BufReader::new(transport::connect(name)?), next_id: 1 })
+    }
+
+    pub fn call(&mut self, method: &str, params: Value) -> anyhow::Result<Value>
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        write_message(self.reader.get_mut(), &request)?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            anyhow::bail!("IPC server closed the connection");
+        }
+
+        let mut response: Value = serde_json::from_str(&line)?;
+        if let Some(error) = response.get("error") {
+            anyhow::bail!(
+                "{}",
+                error.get("message").and_then(Value::as_str).unwrap_or("Unknown IPC error")
+            );
+        }
+
+        Ok(response["result"].take())
+    }
+}
+
+#[cfg(windows)]
+mod transport
+{
+    use std::io::{Read, Write};
+    use windows::{
+        Win32::{
+            Foundation::{
+                CloseHandle, ERROR_BROKEN_PIPE, ERROR_PIPE_BUSY, ERROR_PIPE_CONNECTED, GENERIC_ALL,
+                GENERIC_READ, GENERIC_WRITE, HANDLE,
+            },
+            Security::{
+                ACCESS_ALLOWED_ACE, ACE_FLAGS, ACL, ACL_REVISION, AddAccessAllowedAce,
+                AddMandatoryAce, CreateWellKnownSid, GetLengthSid, GetTokenInformation,
+                InitializeAcl, InitializeSecurityDescriptor, PSECURITY_DESCRIPTOR, PSID,
+                SECURITY_ATTRIBUTES, SECURITY_DESCRIPTOR, SECURITY_MAX_SID_SIZE,
+                SYSTEM_MANDATORY_LABEL_ACE, SetSecurityDescriptorDacl, SetSecurityDescriptorSacl,
+                TOKEN_QUERY, TOKEN_USER, TokenUser, WinHighLabelSid,
+            },
+            Storage::FileSystem::{
+                CreateFileW, FILE_FLAGS_AND_ATTRIBUTES, FILE_SHARE_NONE, FlushFileBuffers,
+                OPEN_EXISTING, PIPE_ACCESS_DUPLEX, ReadFile, WriteFile,
+            },
+            System::{
+                Pipes::{
+                    ConnectNamedPipe, CreateNamedPipeW, PIPE_READMODE_BYTE,
+                    PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES,
+                    PIPE_WAIT, WaitNamedPipeW,
+                },
+                SystemServices::{
+                    SECURITY_DESCRIPTOR_REVISION, SYSTEM_MANDATORY_LABEL_NO_WRITE_UP,
+                },
+                Threading::{GetCurrentProcess, OpenProcessToken},
+            },
+        },
+        core::HSTRING,
+    };
+
+    const BUFFER_SIZE: u32 = 64 * 1024;
+
+    fn pipe_path(name: &str) -> HSTRING
+    {
+        HSTRING::from(format!(r"\\.\pipe\{}", name))
+    }
+
+    pub struct Stream(HANDLE);
+
+    unsafe impl Send for Stream {}
+
+    impl Read for Stream
+    {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize>
+        {
+            let mut bytes_read = 0u32;
+            match unsafe { ReadFile(self.0, Some(buf), Some(&mut bytes_read), None) } {
+                Ok(()) => Ok(bytes_read as usize),
+                Err(e) if e.code() == ERROR_BROKEN_PIPE.to_hresult() => Ok(0),
+                Err(e) => Err(e.into()),
+            }
+        }
+    }
+
+    impl Write for Stream
+    {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize>
+        {
+            let mut bytes_written = 0u32;
+            unsafe { WriteFile(self.0, Some(buf), Some(&mut bytes_written), None) }?;
+            Ok(bytes_written as usize)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()>
+        {
+            unsafe { FlushFileBuffers(self.0) }?;
+            Ok(())
+        }
+    }
+
+    impl Drop for Stream
+    {
+        fn drop(&mut self)
+        {
+            unsafe {
+                let _ = CloseHandle(self.0);
+            }
+        }
+    }
+
+    // Only the user running ihateborders may connect. The user's SID alone is no boundary once
+    // the instance runs elevated: the user's medium-integrity processes share it, and `run` would
+    // start their programs elevated without a UAC prompt. An elevated pipe is therefore labelled
+    // High integrity with no write-up, so only other elevated processes can send requests.
+    struct PipeSecurity
+    {
+        descriptor: Box<SECURITY_DESCRIPTOR>,
+        _acl: Vec<u32>,
+        _label: Option<Vec<u32>>,
+    }
+
+    impl PipeSecurity
+    {
+        fn new(high_integrity: bool) -> std::io::Result<Self>
+        {
+            unsafe {
+                let mut token = HANDLE::default();
+                OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token)?;
+
+                let mut length = 0u32;
+                let _ = GetTokenInformation(token, TokenUser, None, 0, &mut length);
+                let mut user = vec![0u64; (length as usize).div_ceil(8)];
+                let result = GetTokenInformation(
+                    token,
+                    TokenUser,
+                    Some(user.as_mut_ptr() as *mut std::ffi::c_void),
+                    length,
+                    &mut length,
+                );
+                let _ = CloseHandle(token);
+                result?;
+                let sid = (*(user.as_ptr() as *const TOKEN_USER)).User.Sid;
+
+                let acl_size = std::mem::size_of::<ACL>()
+                    + std::mem::size_of::<ACCESS_ALLOWED_ACE>()
+                    + GetLengthSid(sid) as usize;
+                let mut acl = vec![0u32; acl_size.div_ceil(4)];
+                let acl_ptr = acl.as_mut_ptr() as *mut ACL;
+                InitializeAcl(acl_ptr, (acl.len() * 4) as u32, ACL_REVISION)?;
+                AddAccessAllowedAce(acl_ptr, ACL_REVISION, GENERIC_ALL.0, sid)?;
+
+                let mut descriptor = Box::new(SECURITY_DESCRIPTOR::default());
+                let descriptor_ptr = PSECURITY_DESCRIPTOR(
+                    &mut *descriptor as *mut SECURITY_DESCRIPTOR as *mut std::ffi::c_void,
+                );
+                InitializeSecurityDescriptor(descriptor_ptr, SECURITY_DESCRIPTOR_REVISION)?;
+                SetSecurityDescriptorDacl(descriptor_ptr, true, Some(acl_ptr), false)?;
+
+                let label = if high_integrity {
+                    let mut label_sid = vec![0u32; SECURITY_MAX_SID_SIZE.div_ceil(4) as usize];
+                    let label_sid_ptr = PSID(label_sid.as_mut_ptr() as *mut std::ffi::c_void);
+                    let mut label_sid_size = SECURITY_MAX_SID_SIZE;
+                    CreateWellKnownSid(
+                        WinHighLabelSid,
+                        None,
+                        Some(label_sid_ptr),
+                        &mut label_sid_size,
+                    )?;
+
+                    let sacl_size = std::mem::size_of::<ACL>()
+                        + std::mem::size_of::<SYSTEM_MANDATORY_LABEL_ACE>()
+                        + label_sid_size as usize;
+                    let mut sacl = vec![0u32; sacl_size.div_ceil(4)];
+                    let sacl_ptr = sacl.as_mut_ptr() as *mut ACL;
+                    InitializeAcl(sacl_ptr, (sacl.len() * 4) as u32, ACL_REVISION)?;
+                    AddMandatoryAce(
+                        sacl_ptr,
+                        ACL_REVISION,
+                        ACE_FLAGS(0),
+                        SYSTEM_MANDATORY_LABEL_NO_WRITE_UP,
+                        label_sid_ptr,
+                    )?;
+                    SetSecurityDescriptorSacl(descriptor_ptr, true, Some(sacl_ptr), false)?;
+
+                    Some(sacl)
+                } else {
+                    None
+                };
+
+                Ok(Self { descriptor, _acl: acl, _label: label })
+            }
+        }
+
+        fn attributes(&self) -> SECURITY_ATTRIBUTES
+        {
+            SECURITY_ATTRIBUTES {
+                nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+                lpSecurityDescriptor: &*self.descriptor as *const SECURITY_DESCRIPTOR
+                    as *mut std::ffi::c_void,
+                bInheritHandle: false.into(),
+            }
+        }
+    }
+
+    pub struct Listener
+    {
+        name: HSTRING,
+        security: PipeSecurity,
+    }
+
+    unsafe impl Send for Listener {}
+
+    impl Listener
+    {
+        pub fn bind(name: &str) -> std::io::Result<Self>
+        {
+            Ok(Self {
+                name: pipe_path(name),
+                security: PipeSecurity::new(crate::process::is_elevated())?,
+            })
+        }
+
+        pub fn accept(&self) -> std::io::Result<Stream>
+        {
+            let attributes = self.security.attributes();
+
+            unsafe {
+                let handle = CreateNamedPipeW(
+                    &self.name,
+                    PIPE_ACCESS_DUPLEX,
+                    PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                    PIPE_UNLIMITED_INSTANCES,
+                    BUFFER_SIZE,
+                    BUFFER_SIZE,
+                    0,
+                    Some(&attributes),
+                );
+
+                if handle.is_invalid() {
+                    return Err(std::io::Error::last_os_error());
+                }
+
+                let stream = Stream(handle);
+
+                match ConnectNamedPipe(handle, None) {
+                    Ok(()) => Ok(stream),
+                    Err(e) if e.code() == ERROR_PIPE_CONNECTED.to_hresult() => Ok(stream),
+                    Err(e) => Err(e.into()),
+                }
+            }
+        }
+    }
+
+    pub fn connect(name: &str) -> std::io::Result<Stream>
+    {
+        let name = pipe_path(name);
+
+        for _ in 0..5 {
+            let result = unsafe {
+                CreateFileW(
+                    &name,
+                    (GENERIC_READ | GENERIC_WRITE).0,
+                    FILE_SHARE_NONE,
+                    None,
+                    OPEN_EXISTING,
+                    FILE_FLAGS_AND_ATTRIBUTES(0),
+                    None,
+                )
+            };
+
+            match result {
+                Ok(handle) => return Ok(Stream(handle)),
+                Err(e) if e.code() == ERROR_PIPE_BUSY.to_hresult() => unsafe {
+                    let _ = WaitNamedPipeW(&name, 1000);
+                },
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Err(std::io::Error::new(std::io::ErrorKind::TimedOut, "IPC pipe is busy"))
+    }
+
+    #[cfg(test)]
+    mod tests
+    {
+        use super::*;
+        use windows::{
+            Win32::{
+                Security::{EqualSid, GetAce, GetSecurityDescriptorSacl},
+                System::SystemServices::SYSTEM_MANDATORY_LABEL_ACE_TYPE,
+            },
+            core::BOOL,
+        };
+
+        fn label(security: &PipeSecurity) -> Option<(u32, bool)>
+        {
+            unsafe {
+                let descriptor = PSECURITY_DESCRIPTOR(
+                    &*security.descriptor as *const SECURITY_DESCRIPTOR as *mut std::ffi::c_void,
+                );
+                let (mut present, mut defaulted) = (BOOL(0), BOOL(0));
+                let mut sacl: *mut ACL = std::ptr::null_mut();
+                GetSecurityDescriptorSacl(descriptor, &mut present, &mut sacl, &mut defaulted)
+                    .unwrap();
+                if !present.as_bool() || sacl.is_null() {
+                    return None;
+                }
+
+                let mut ace: *mut std::ffi::c_void = std::ptr::null_mut();
+                GetAce(sacl, 0, &mut ace).unwrap();
+                let ace = &*(ace as *const SYSTEM_MANDATORY_LABEL_ACE);
+                assert_eq!(u32::from(ace.Header.AceType), SYSTEM_MANDATORY_LABEL_ACE_TYPE);
+
+                let mut high = vec![0u32; SECURITY_MAX_SID_SIZE.div_ceil(4) as usize];
+                let high_ptr = PSID(high.as_mut_ptr() as *mut std::ffi::c_void);
+                let mut high_size = SECURITY_MAX_SID_SIZE;
+                CreateWellKnownSid(WinHighLabelSid, None, Some(high_ptr), &mut high_size).unwrap();
+                let sid = PSID(&ace.SidStart as *const u32 as *mut std::ffi::c_void);
+
+                Some((ace.Mask, EqualSid(sid, high_ptr).is_ok()))
+            }
+        }
+
+        #[test]
+        fn elevated_pipes_are_labelled_high_integrity()
+        {
+            let security = PipeSecurity::new(true).unwrap();
+
+            assert_eq!(label(&security), Some((SYSTEM_MANDATORY_LABEL_NO_WRITE_UP, true)));
+        }
+
+        #[test]
+        fn standard_pipes_carry_no_label()
+        {
+            let security = PipeSecurity::new(false).unwrap();
+
+            assert_eq!(label(&security), None);
+        }
+    }
+}
+
+#[cfg(unix)]
+mod transport
+{
+    use std::{
+        os::unix::{
+            fs::PermissionsExt,
+            net::{UnixListener, UnixStream},
+        },
+        path::PathBuf,
+    };
+
+    pub type Stream = UnixStream;
+
+    fn socket_path(name: &str) -> PathBuf
+    {
+        std::env::var_os("XDG_RUNTIME_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(std::env::temp_dir)
+            .join(format!("{}.sock", name))
+    }
+
+    pub struct Listener(UnixListener);
+
+    impl Listener
+    {
+        pub fn bind(name: &str) -> std::io::Result<Self>
+        {
+            let path = socket_path(name);
+            if path.exists() && UnixStream::connect(&path).is_err() {
+                std::fs::remove_file(&path)?;
+            }
+
+            let listener = UnixListener::bind(&path)?;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+
+            Ok(Self(listener))
+        }
+
+        pub fn accept(&self) -> std::io::Result<Stream>
+        {
+            self.0.accept().map(|(stream, _)| stream)
+        }
+    }
+
+    pub fn connect(name: &str) -> std::io::Result<Stream>
+    {
+        UnixStream::connect(socket_path(name))
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::window_manager::WindowInfo;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static NEXT_PIPE: AtomicU32 = AtomicU32::new(0);
+
+    fn start() -> (Client, WindowManager)
+    {
+        let name = format!(
+            "ihateborders-test-{}-{}",
+            std::process::id(),
+            NEXT_PIPE.fetch_add(1, Ordering::Relaxed)
+        );
+        let window_manager = WindowManager::new();
+        let state = ServerState {
+            window_manager: window_manager.clone(),
+            config: Arc::new(Mutex::new(Config::default())),
+            egui_ctx: egui::Context::default(),
+        };
+
+        let listener = transport::Listener::bind(&name).unwrap();
+        std::thread::spawn(move || serve(listener, state));
+
+        for _ in 0..40 {
+            if let Ok(client) = Client::connect_to(&name) {
+                return (client, window_manager);
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        panic!("Could not connect to {}", name);
+    }
+
+    #[test]
+    fn lists_displays_and_windows()
+    {
+        let (mut client, _) = start();
+
+        let displays = client.call("list_displays", Value::Null).unwrap();
+        let displays = displays.as_array().unwrap();
+        assert!(!displays.is_empty());
+        assert!(displays.iter().all(|display| display["width"].as_i64() > Some(0)));
+
+        let windows = client.call("list_windows", Value::Null).unwrap();
+        let windows = windows.as_array().unwrap();
+        assert!(windows.iter().all(|window| window["hwnd"].is_i64()));
+        assert!(windows.iter().all(|window| window["title"] != "ihateborders"));
+    }
+
+    #[test]
+    fn rejects_unknown_methods()
+    {
+        let (mut client, _) = start();
+
+        let error = client.call("make_everything_borderless", Value::Null).unwrap_err();
+        assert_eq!(error.to_string(), "Method not found: make_everything_borderless");
+
+        assert!(client.call("list_displays", Value::Null).unwrap().is_array());
+    }
+
+    #[test]
+    fn rejects_malformed_params()
+    {
+        let (mut client, _) = start();
+
+        for (method, params) in [
+            ("restore", Value::Null),
+            ("restore", json!({ "hwnd": "not a handle" })),
+            ("set_borderless", json!({ "hwnd": 1, "frame_mode": "sideways" })),
+            ("set_modifiers", json!({ "hwnd": 1, "opacity": 300 })),
+        ] {
+            let error = client.call(method, params).unwrap_err().to_string();
+            assert!(error.starts_with("Invalid params:"), "{}: {}", method, error);
+        }
+    }
+
+    #[test]
+    fn streams_window_events_to_subscribers()
+    {
+        let (mut client, window_manager) = start();
+
+        assert_eq!(client.call("subscribe", Value::Null).unwrap(), Value::Bool(true));
+
+        window_manager.set_windows(vec![WindowInfo {
+            hwnd: 0x1234,
+            title: "Test window".to_string(),
+            process_name: "test.exe".to_string(),
+            ..Default::default()
+        }]);
+
+        let mut line = String::new();
+        client.reader.read_line(&mut line).unwrap();
+        let notification: Value = serde_json::from_str(&line).unwrap();
+
+        assert_eq!(notification["method"], "window_event");
+        assert_eq!(notification["params"]["event"], "opened");
+        assert_eq!(notification["params"]["hwnd"], 0x1234);
+        assert_eq!(notification["params"]["title"], "Test window");
+        assert_eq!(notification["params"]["process_name"], "test.exe");
+    }
+}
@@ -0,0 +1,346 @@
+use crate::{
+    app,
+    config::{Config, Profile},
+    logging, process,
+    window_manager::{WindowInfo, WindowManager},
+};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::Path,
+    process::Child,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+const WINDOW_TIMEOUT: Duration = Duration::from_secs(300);
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitAction
+{
+    #[default]
+    None,
+    Restore,
+    Quit,
+}
+
+impl ExitAction
+{
+    pub fn parse(text: &str) -> anyhow::Result<Self>
+    {
+        match text.to_ascii_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "restore" => Ok(Self::Restore),
+            "quit" | "exit" => Ok(Self::Quit),
+            _ => anyhow::bail!("Unknown exit action '{}' (expected none, restore or quit)", text),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunOptions
+{
+    pub program: String,
+    pub args: Vec<String>,
+    pub profile: Option<String>,
+    pub on_exit: ExitAction,
+}
+
+pub fn run(
+    options: &RunOptions,
+    window_manager: WindowManager,
+    config: &Arc<Mutex<Config>>,
+    egui_ctx: egui::Context,
+) -> anyhow::Result<u32>
+{
+    let profile = {
+        let config = config.lock().unwrap();
+
+        match &options.profile {
+            Some(name) => config
+                .find_profile(name)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Unknown profile '{}'", name))?,
+            None => Profile {
+                name: options.program.clone(),
+                resize_to_screen: config.resize_to_screen,
+                display: config.display.clone(),
+                ..Default::default()
+            },
+        }
+    };
+
+    spawn(&options.program, &options.args, profile, options.on_exit, window_manager, egui_ctx)
+}
+
+pub fn spawn(
+    program: &str,
+    args: &[String],
+    profile: Profile,
+    on_exit: ExitAction,
+    window_manager: WindowManager,
+    egui_ctx: egui::Context,
+) -> anyhow::Result<u32>
+{
+    let path = Path::new(program);
+    let mut command = std::process::Command::new(path);
+    command.args(args);
+
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        command.current_dir(dir);
+    }
+
+    let child =
+        command.spawn().map_err(|e| anyhow::anyhow!("Failed to start '{}': {}", program, e))?;
+    let process_id = child.id();
+    logging::info(
+        "automation",
+        format!(
+            "Launched {:?} pid={} profile={:?} on_exit={:?}",
+            program, process_id, profile.name, on_exit
+        ),
+    );
+
+    std::thread::spawn(move || {
+        if let Err(e) = watch(child, &profile, on_exit, &window_manager, &egui_ctx) {
+            logging::warn("automation", format!("Launch of {:?} failed: {}", profile.name, e));
+        }
+    });
+
+    Ok(process_id)
+}
+
+fn watch(
+    child: Child,
+    profile: &Profile,
+    on_exit: ExitAction,
+    window_manager: &WindowManager,
+    egui_ctx: &egui::Context,
+) -> anyhow::Result<()>
+{
+    let mut tree = ProcessTree::new(child);
+    let started = Instant::now();
+
+    let window = loop {
+        if !tree.update() {
+            anyhow::bail!("Process exited before opening a window");
+        }
+
+        if started.elapsed() > WINDOW_TIMEOUT {
+            anyhow::bail!("Timed out waiting for a window");
+        }
+
+        window_manager.refresh_windows();
+
+        if let Some(window) = window_manager
+            .get_windows()
+            .into_iter()
+            .find(|window| tree.contains(window.process_id) && qualifies(profile, window))
+        {
+            logging::info(
+                "automation",
+                format!(
+                    "Window of {:?} appeared after {} ms hwnd=0x{:X} title={} process={:?}",
+                    profile.name,
+                    started.elapsed().as_millis(),
+                    window.hwnd,
+                    logging::title(&window.title),
+                    window.process_name
+                ),
+            );
+            break window;
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    };
+
+    let displays = window_manager.get_displays();
+    window_manager.apply_profile(window.hwnd, profile, &displays)?;
+    egui_ctx.request_repaint();
+
+    if on_exit == ExitAction::None {
+        return Ok(());
+    }
+
+    while tree.update() {
+        std::thread::sleep(Duration::from_secs(1));
+    }
+
+    logging::info(
+        "automation",
+        format!("Process tree of {:?} exited, running {:?}", profile.name, on_exit),
+    );
+
+    match on_exit {
+        ExitAction::None => {},
+        ExitAction::Restore => {
+            if window_manager.is_borderless(window.hwnd) {
+                let _ = window_manager.restore_borders(window.hwnd);
+            }
+        },
+        ExitAction::Quit => app::quit(egui_ctx),
+    }
+
+    egui_ctx.request_repaint();
+
+    Ok(())
+}
+
+fn qualifies(profile: &Profile, window: &WindowInfo) -> bool
+{
+    let title_matches = profile
+        .title_contains
+        .as_ref()
+        .is_none_or(|pattern| window.title.to_lowercase().contains(&pattern.to_lowercase()));
+
+    let class_matches = profile
+        .class_name
+        .as_ref()
+        .is_none_or(|class_name| class_name.eq_ignore_ascii_case(&window.class_name));
+
+    !window.title.trim().is_empty() && !window.is_owned && title_matches && class_matches
+}
+
+struct ProcessTree
+{
+    root: Child,
+    members: HashMap<u32, u64>,
+}
+
+impl ProcessTree
+{
+    fn new(root: Child) -> Self
+    {
+        let root_id = root.id();
+        let members = HashMap::from([(root_id, process::creation_time(root_id).unwrap_or(0))]);
+        Self { root, members }
+    }
+
+    fn contains(&self, process_id: u32) -> bool
+    {
+        self.members.contains_key(&process_id)
+    }
+
+    fn update(&mut self) -> bool
+    {
+        let root_running = matches!(self.root.try_wait(), Ok(None));
+        let processes = process::list_processes();
+
+        adopt(&mut self.members, &processes, process::creation_time);
+
+        let root_id = self.root.id();
+
+        root_running || processes.iter().any(|(id, _)| *id != root_id && self.contains(*id))
+    }
+}
+
+// Process ids are recycled, so members are kept with their creation time: a member whose id now
+// belongs to a process started at another time is dropped, and a child only joins the tree when
+// it started after the member it names as its parent.
+fn adopt(
+    members: &mut HashMap<u32, u64>,
+    processes: &[(u32, u32)],
+    creation_time: impl Fn(u32) -> Option<u64>,
+)
+{
+    let mut times = HashMap::new();
+    let mut created = |id: u32| *times.entry(id).or_insert_with(|| creation_time(id));
+
+    for (id, _) in processes {
+        if let Some(&time) = members.get(id)
+            && created(*id).is_some_and(|current| current != time)
+        {
+            members.remove(id);
+        }
+    }
+
+    loop {
+        let children: Vec<(u32, u64)> = processes
+            .iter()
+            .filter(|(id, parent)| !members.contains_key(id) && members.contains_key(parent))
+            .filter_map(|(id, parent)| {
+                let time = created(*id)?;
+                (time >= members[parent]).then_some((*id, time))
+            })
+            .collect();
+
+        if children.is_empty() {
+            break;
+        }
+
+        members.extend(children);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn times(entries: &[(u32, u64)]) -> impl Fn(u32) -> Option<u64>
+    {
+        let entries: HashMap<u32, u64> = entries.iter().copied().collect();
+        move |id| entries.get(&id).copied()
+    }
+
+    fn sorted(members: &HashMap<u32, u64>) -> Vec<u32>
+    {
+        let mut ids: Vec<u32> = members.keys().copied().collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn parses_exit_actions()
+    {
+        assert_eq!(ExitAction::parse("none").unwrap(), ExitAction::None);
+        assert_eq!(ExitAction::parse("Restore").unwrap(), ExitAction::Restore);
+        assert_eq!(ExitAction::parse("quit").unwrap(), ExitAction::Quit);
+        assert_eq!(ExitAction::parse("EXIT").unwrap(), ExitAction::Quit);
+        assert_eq!(
+            ExitAction::parse("close").unwrap_err().to_string(),
+            "Unknown exit action 'close' (expected none, restore or quit)"
+        );
+    }
+
+    #[test]
+    fn adopts_children_and_grandchildren()
+    {
+        let mut members = HashMap::from([(10, 100)]);
+        let processes = [(10, 1), (30, 20), (20, 10), (40, 1)];
+
+        adopt(&mut members, &processes, times(&[(10, 100), (20, 110), (30, 120), (40, 130)]));
+
+        assert_eq!(sorted(&members), [10, 20, 30]);
+    }
+
+    #[test]
+    fn ignores_processes_older_than_their_parent()
+    {
+        // 20 names a parent whose id was reused by the launched process after 20 started.
+        let mut members = HashMap::from([(10, 100)]);
+
+        adopt(&mut members, &[(10, 1), (20, 10)], times(&[(10, 100), (20, 50)]));
+
+        assert_eq!(sorted(&members), [10]);
+    }
+
+    #[test]
+    fn drops_members_whose_id_was_reused()
+    {
+        let mut members = HashMap::from([(10, 100), (20, 110)]);
+
+        // 20 exited and its id now belongs to an unrelated process with a child of its own.
+        adopt(
+            &mut members,
+            &[(10, 1), (20, 5), (30, 20)],
+            times(&[(10, 100), (20, 200), (30, 210)]),
+        );
+
+        assert_eq!(sorted(&members), [10]);
+    }
+}
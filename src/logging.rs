@@ -0,0 +1,233 @@
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs::{File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex, MutexGuard},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+const KEPT_FILES: usize = 3;
+const RECENT_ENTRIES: usize = 500;
+
+static STATE: LazyLock<Mutex<LogState>> = LazyLock::new(|| Mutex::new(LogState::default()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel
+{
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+}
+
+impl LogLevel
+{
+    pub const ALL: [Self; 4] = [Self::Error, Self::Warn, Self::Info, Self::Debug];
+
+    pub fn label(self) -> &'static str
+    {
+        match self {
+            Self::Error => "Errors",
+            Self::Warn => "Warnings",
+            Self::Info => "Info",
+            Self::Debug => "Debug",
+        }
+    }
+
+    fn tag(self) -> &'static str
+    {
+        match self {
+            Self::Error => "ERROR",
+            Self::Warn => "WARN",
+            Self::Info => "INFO",
+            Self::Debug => "DEBUG",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogEntry
+{
+    pub time: String,
+    pub level: LogLevel,
+    pub target: &'static str,
+    pub message: String,
+}
+
+impl LogEntry
+{
+    pub fn line(&self) -> String
+    {
+        format!("{} {:<5} [{}] {}", self.time, self.level.tag(), self.target, self.message)
+    }
+}
+
+#[derive(Default)]
+struct LogState
+{
+    level: LogLevel,
+    redact_titles: bool,
+    file: Option<File>,
+    size: u64,
+    recent: VecDeque<LogEntry>,
+}
+
+impl LogState
+{
+    fn write(&mut self, line: &str)
+    {
+        if self.file.is_none() || self.size >= MAX_FILE_SIZE {
+            self.open();
+        }
+
+        if let Some(file) = &mut self.file
+            && writeln!(file, "{}", line).is_ok()
+        {
+            self.size += line.len() as u64 + 1;
+        }
+    }
+
+    fn open(&mut self)
+    {
+        self.file = None;
+
+        let Some(path) = path() else {
+            return;
+        };
+
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+
+        if std::fs::metadata(&path).is_ok_and(|metadata| metadata.len() >= MAX_FILE_SIZE) {
+            rotate(&path);
+        }
+
+        if let Ok(file) = OpenOptions::new().create(true).append(true).open(&path) {
+            self.size = file.metadata().map_or(0, |metadata| metadata.len());
+            self.file = Some(file);
+        }
+    }
+}
+
+pub fn configure(level: LogLevel, redact_titles: bool)
+{
+    let mut state = state();
+    state.level = level;
+    state.redact_titles = redact_titles;
+}
+
+pub fn error(target: &'static str, message: impl Into<String>)
+{
+    log(LogLevel::Error, target, message.into());
+}
+
+pub fn warn(target: &'static str, message: impl Into<String>)
+{
+    log(LogLevel::Warn, target, message.into());
+}
+
+pub fn info(target: &'static str, message: impl Into<String>)
+{
+    log(LogLevel::Info, target, message.into());
+}
+
+pub fn debug(target: &'static str, message: impl Into<String>)
+{
+    log(LogLevel::Debug, target, message.into());
+}
+
+pub fn enabled(level: LogLevel) -> bool
+{
+    level <= state().level
+}
+
+pub fn title(title: &str) -> String
+{
+    if state().redact_titles {
+        "<redacted>".to_string()
+    } else {
+        format!("{:?}", title)
+    }
+}
+
+pub fn recent() -> Vec<LogEntry>
+{
+    state().recent.iter().cloned().collect()
+}
+
+pub fn path() -> Option<PathBuf>
+{
+    Config::dir().map(|dir| dir.join("ihateborders.log"))
+}
+
+// A panic while logging must not take every later log call (and the UI reading the viewer) down
+// with it, so a poisoned lock is recovered instead of unwrapped.
+fn state() -> MutexGuard<'static, LogState>
+{
+    STATE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn log(level: LogLevel, target: &'static str, message: String)
+{
+    let mut state = state();
+    if level > state.level {
+        return;
+    }
+
+    let entry = LogEntry { time: timestamp(), level, target, message };
+    state.write(&entry.line());
+
+    if state.recent.len() >= RECENT_ENTRIES {
+        state.recent.pop_front();
+    }
+    state.recent.push_back(entry);
+}
+
+fn rotate(path: &Path)
+{
+    for index in (1..KEPT_FILES).rev() {
+        let _ = std::fs::rename(rotated_path(path, index - 1), rotated_path(path, index));
+    }
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf
+{
+    if index == 0 { path.to_path_buf() } else { path.with_extension(format!("{}.log", index)) }
+}
+
+fn timestamp() -> String
+{
+    let elapsed = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = elapsed.as_secs();
+    let time = seconds % 86_400;
+
+    // Days since the epoch to a proleptic Gregorian date, after Howard Hinnant's civil_from_days.
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time / 60 % 60,
+        time % 60,
+        elapsed.subsec_millis()
+    )
+}
@@ -1,16 +1,83 @@
 #![windows_subsystem = "windows"]
 
 mod app;
+mod backdrop;
+mod borderless_gaming;
+mod bundle;
+mod cli;
+mod config;
+mod cursor;
+mod history;
+mod hotkeys;
+mod instance;
+mod ipc;
+mod launcher;
+mod logging;
+mod overlay;
+mod picker;
+mod process;
+mod taskbar;
+mod tray;
 mod ui;
 mod window_manager;
 
 use app::{BorderlessApp, create_app_options};
+use cli::Command;
+use config::Config;
+use instance::InstanceLock;
 
 fn main() -> Result<(), eframe::Error>
 {
-    eframe::run_native(
+    let command = match Command::parse(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            logging::error("cli", e.to_string());
+            return Ok(());
+        },
+    };
+
+    if let Command::Relaunch { after, .. } = &command {
+        instance::wait_for_exit(*after);
+    }
+
+    let _instance_lock = match InstanceLock::acquire() {
+        Ok(Some(lock)) => Some(lock),
+        Ok(None) => {
+            if let Err(e) = instance::forward(&command) {
+                logging::error(
+                    "instance",
+                    format!("Failed to forward command to the running instance: {}", e),
+                );
+            }
+            return Ok(());
+        },
+        Err(e) => {
+            logging::error("instance", format!("Failed to acquire single-instance lock: {}", e));
+            None
+        },
+    };
+
+    taskbar::recover();
+
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        taskbar::restore();
+        cursor::release();
+        default_hook(info);
+    }));
+
+    let config = Config::load();
+    logging::configure(config.log_level, config.redact_titles);
+    logging::info("app", format!("Starting ihateborders {}", env!("CARGO_PKG_VERSION")));
+
+    let result = eframe::run_native(
         "ihateborders",
-        create_app_options(),
-        Box::new(|cc| Ok(Box::new(BorderlessApp::new(cc)))),
-    )
+        create_app_options(&config),
+        Box::new(|cc| Ok(Box::new(BorderlessApp::new(cc, command, config)))),
+    );
+
+    taskbar::restore();
+    cursor::release();
+
+    result
 }
@@ -0,0 +1,265 @@
+use windows::{
+    Win32::{
+        Foundation::{COLORREF, HWND, LPARAM, LRESULT, RECT, WPARAM},
+        Graphics::Gdi::{
+            CombineRgn, CreateRectRgn, CreateSolidBrush, DeleteObject, FillRect, HBRUSH, HDC,
+            RGN_DIFF, RGN_OR, SetWindowRgn,
+        },
+        System::LibraryLoader::GetModuleHandleW,
+        UI::WindowsAndMessaging::{
+            CreateWindowExW, DefWindowProcW, DestroyWindow, GW_HWNDPREV, GWLP_USERDATA,
+            GetClientRect, GetWindow, GetWindowLongPtrW, HWND_TOPMOST, IsWindowVisible, LWA_ALPHA,
+            RegisterClassW, SW_HIDE, SWP_NOACTIVATE, SWP_SHOWWINDOW, SetLayeredWindowAttributes,
+            SetWindowLongPtrW, SetWindowPos, ShowWindow, WM_ERASEBKGND, WNDCLASSW, WS_EX_LAYERED,
+            WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_EX_TRANSPARENT, WS_POPUP,
+        },
+    },
+    core::{PCWSTR, w},
+};
+
+pub struct Outline
+{
+    hwnd: HWND,
+    brush: HBRUSH,
+    thickness: i32,
+}
+
+impl Outline
+{
+    pub fn new(color: (u8, u8, u8), thickness: i32) -> anyhow::Result<Self>
+    {
+        Self::create(color, thickness, 230)
+    }
+
+    pub fn ghost(color: (u8, u8, u8)) -> anyhow::Result<Self>
+    {
+        Self::create(color, 0, 96)
+    }
+
+    fn create(color: (u8, u8, u8), thickness: i32, alpha: u8) -> anyhow::Result<Self>
+    {
+        unsafe {
+            let instance = GetModuleHandleW(PCWSTR::null())?;
+            let class_name = w!("ihateborders_outline");
+
+            let window_class = WNDCLASSW {
+                lpfnWndProc: Some(outline_window_proc),
+                hInstance: instance.into(),
+                lpszClassName: class_name,
+                ..Default::default()
+            };
+            RegisterClassW(&window_class);
+
+            let hwnd = CreateWindowExW(
+                WS_EX_LAYERED
+                    | WS_EX_TRANSPARENT
+                    | WS_EX_TOOLWINDOW
+                    | WS_EX_TOPMOST
+                    | WS_EX_NOACTIVATE,
+                class_name,
+                w!("ihateborders outline"),
+                WS_POPUP,
+                0,
+                0,
+                0,
+                0,
+                None,
+                None,
+                Some(instance.into()),
+                None,
+            )?;
+
+            SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA)?;
+
+            let (red, green, blue) = color;
+            let brush =
+                CreateSolidBrush(COLORREF(red as u32 | (green as u32) << 8 | (blue as u32) << 16));
+            SetWindowLongPtrW(hwnd, GWLP_USERDATA, brush.0 as isize);
+
+            Ok(Self { hwnd, brush, thickness })
+        }
+    }
+
+    pub fn show(&self, rect: RECT)
+    {
+        let width = rect.right - rect.left;
+        let height = rect.bottom - rect.top;
+        let thickness = self.thickness;
+
+        unsafe {
+            let region = CreateRectRgn(0, 0, width, height);
+
+            if thickness > 0 && width > thickness * 2 && height > thickness * 2 {
+                let inner =
+                    CreateRectRgn(thickness, thickness, width - thickness, height - thickness);
+                CombineRgn(Some(region), Some(region), Some(inner), RGN_DIFF);
+                let _ = DeleteObject(inner.into());
+            }
+
+            SetWindowRgn(self.hwnd, Some(region), true);
+
+            let _ = SetWindowPos(
+                self.hwnd,
+                Some(HWND_TOPMOST),
+                rect.left,
+                rect.top,
+                width,
+                height,
+                SWP_NOACTIVATE | SWP_SHOWWINDOW,
+            );
+        }
+    }
+
+    pub fn hide(&self)
+    {
+        unsafe {
+            let _ = ShowWindow(self.hwnd, SW_HIDE);
+        }
+    }
+}
+
+impl Drop for Outline
+{
+    fn drop(&mut self)
+    {
+        unsafe {
+            let _ = DestroyWindow(self.hwnd);
+            let _ = DeleteObject(self.brush.into());
+        }
+    }
+}
+
+unsafe extern "system" fn outline_window_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT
+{
+    unsafe {
+        if msg == WM_ERASEBKGND {
+            let brush = HBRUSH(GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *mut std::ffi::c_void);
+            let mut rect = RECT::default();
+            let _ = GetClientRect(hwnd, &mut rect);
+            FillRect(HDC(wparam.0 as *mut std::ffi::c_void), &rect, brush);
+            return LRESULT(1);
+        }
+
+        DefWindowProcW(hwnd, msg, wparam, lparam)
+    }
+}
+
+pub struct Backdrop
+{
+    hwnd: HWND,
+    brush: HBRUSH,
+    color: [u8; 3],
+    placed: Option<(RECT, Vec<RECT>)>,
+}
+
+impl Backdrop
+{
+    pub fn new(color: [u8; 3]) -> anyhow::Result<Self>
+    {
+        unsafe {
+            let instance = GetModuleHandleW(PCWSTR::null())?;
+            let class_name = w!("ihateborders_backdrop");
+
+            let window_class = WNDCLASSW {
+                lpfnWndProc: Some(outline_window_proc),
+                hInstance: instance.into(),
+                lpszClassName: class_name,
+                ..Default::default()
+            };
+            RegisterClassW(&window_class);
+
+            let hwnd = CreateWindowExW(
+                WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
+                class_name,
+                w!("ihateborders backdrop"),
+                WS_POPUP,
+                0,
+                0,
+                0,
+                0,
+                None,
+                None,
+                Some(instance.into()),
+                None,
+            )?;
+
+            let [red, green, blue] = color;
+            let brush =
+                CreateSolidBrush(COLORREF(red as u32 | (green as u32) << 8 | (blue as u32) << 16));
+            SetWindowLongPtrW(hwnd, GWLP_USERDATA, brush.0 as isize);
+
+            Ok(Self { hwnd, brush, color, placed: None })
+        }
+    }
+
+    pub fn color(&self) -> [u8; 3]
+    {
+        self.color
+    }
+
+    pub fn show_below(&mut self, target: HWND, monitor: RECT, regions: Vec<RECT>)
+    {
+        unsafe {
+            let placed = Some((monitor, regions));
+            let above = GetWindow(self.hwnd, GW_HWNDPREV).ok();
+
+            if self.placed == placed
+                && above == Some(target)
+                && IsWindowVisible(self.hwnd).as_bool()
+            {
+                return;
+            }
+
+            let (monitor, regions) = placed.as_ref().unwrap();
+            let region = CreateRectRgn(0, 0, 0, 0);
+            for rect in regions {
+                let part = CreateRectRgn(
+                    rect.left - monitor.left,
+                    rect.top - monitor.top,
+                    rect.right - monitor.left,
+                    rect.bottom - monitor.top,
+                );
+                CombineRgn(Some(region), Some(region), Some(part), RGN_OR);
+                let _ = DeleteObject(part.into());
+            }
+            SetWindowRgn(self.hwnd, Some(region), true);
+
+            let _ = SetWindowPos(
+                self.hwnd,
+                Some(target),
+                monitor.left,
+                monitor.top,
+                monitor.right - monitor.left,
+                monitor.bottom - monitor.top,
+                SWP_NOACTIVATE | SWP_SHOWWINDOW,
+            );
+
+            self.placed = placed;
+        }
+    }
+
+    pub fn hide(&mut self)
+    {
+        if self.placed.take().is_some() {
+            unsafe {
+                let _ = ShowWindow(self.hwnd, SW_HIDE);
+            }
+        }
+    }
+}
+
+impl Drop for Backdrop
+{
+    fn drop(&mut self)
+    {
+        unsafe {
+            let _ = DestroyWindow(self.hwnd);
+            let _ = DeleteObject(self.brush.into());
+        }
+    }
+}
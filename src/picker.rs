@@ -0,0 +1,177 @@
+use crate::{logging, overlay::Outline, window_manager};
+use eframe::egui;
+use std::{cell::RefCell, sync::mpsc::Receiver};
+use windows::{
+    Win32::{
+        Foundation::{COLORREF, HWND, LPARAM, LRESULT, POINT, WPARAM},
+        System::LibraryLoader::GetModuleHandleW,
+        UI::{
+            Input::KeyboardAndMouse::VK_ESCAPE,
+            WindowsAndMessaging::{
+                CreateWindowExW, DefWindowProcW, DispatchMessageW, GetCursorPos, GetMessageW,
+                GetSystemMetrics, IDC_CROSS, LWA_ALPHA, LoadCursorW, MSG, PostMessageW,
+                PostQuitMessage, RegisterClassW, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN,
+                SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN, SW_SHOW, SetForegroundWindow,
+                SetLayeredWindowAttributes, ShowWindow, TranslateMessage, WA_INACTIVE, WM_ACTIVATE,
+                WM_CLOSE, WM_DESTROY, WM_KEYDOWN, WM_LBUTTONDOWN, WM_MOUSEMOVE, WM_RBUTTONDOWN,
+                WNDCLASSW, WS_EX_LAYERED, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_POPUP,
+            },
+        },
+    },
+    core::{PCWSTR, w},
+};
+
+thread_local! {
+    static PICKER_STATE: RefCell<Option<PickerState>> = const { RefCell::new(None) };
+}
+
+struct PickerState
+{
+    outline: Outline,
+    target: Option<isize>,
+    picked: Option<isize>,
+}
+
+pub fn start(egui_ctx: egui::Context) -> Receiver<Option<isize>>
+{
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    std::thread::spawn(move || {
+        let picked = run_picker().unwrap_or_else(|e| {
+            logging::error("picker", format!("Failed to start window picker: {}", e));
+            None
+        });
+
+        let _ = sender.send(picked);
+        egui_ctx.request_repaint();
+    });
+
+    receiver
+}
+
+fn run_picker() -> anyhow::Result<Option<isize>>
+{
+    unsafe {
+        let instance = GetModuleHandleW(PCWSTR::null())?;
+        let class_name = w!("ihateborders_picker");
+
+        let window_class = WNDCLASSW {
+            lpfnWndProc: Some(picker_window_proc),
+            hInstance: instance.into(),
+            lpszClassName: class_name,
+            hCursor: LoadCursorW(None, IDC_CROSS)?,
+            ..Default::default()
+        };
+        RegisterClassW(&window_class);
+
+        let outline = Outline::new((255, 176, 64), 4)?;
+
+        let hwnd = CreateWindowExW(
+            WS_EX_LAYERED | WS_EX_TOPMOST | WS_EX_TOOLWINDOW,
+            class_name,
+            w!("ihateborders picker"),
+            WS_POPUP,
+            GetSystemMetrics(SM_XVIRTUALSCREEN),
+            GetSystemMetrics(SM_YVIRTUALSCREEN),
+            GetSystemMetrics(SM_CXVIRTUALSCREEN),
+            GetSystemMetrics(SM_CYVIRTUALSCREEN),
+            None,
+            None,
+            Some(instance.into()),
+            None,
+        )?;
+
+        SetLayeredWindowAttributes(hwnd, COLORREF(0), 1, LWA_ALPHA)?;
+
+        PICKER_STATE.with(|state| {
+            *state.borrow_mut() = Some(PickerState { outline, target: None, picked: None })
+        });
+
+        let _ = ShowWindow(hwnd, SW_SHOW);
+        let _ = SetForegroundWindow(hwnd);
+        update_target();
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+
+        let state = PICKER_STATE.with(|state| state.borrow_mut().take());
+
+        Ok(state.and_then(|state| state.picked))
+    }
+}
+
+fn update_target()
+{
+    let mut point = POINT::default();
+    if unsafe { GetCursorPos(&mut point) }.is_err() {
+        return;
+    }
+
+    let target = window_manager::window_at(point.x, point.y);
+
+    PICKER_STATE.with(|state| {
+        if let Some(state) = state.borrow_mut().as_mut() {
+            if state.target == target {
+                return;
+            }
+
+            state.target = target;
+            match target.and_then(window_manager::frame_rect) {
+                Some(rect) => state.outline.show(rect),
+                None => state.outline.hide(),
+            }
+        }
+    });
+}
+
+fn cancel(hwnd: HWND)
+{
+    unsafe {
+        let _ = PostMessageW(Some(hwnd), WM_CLOSE, WPARAM(0), LPARAM(0));
+    }
+}
+
+unsafe extern "system" fn picker_window_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT
+{
+    match msg {
+        WM_MOUSEMOVE => {
+            update_target();
+            LRESULT(0)
+        },
+        WM_LBUTTONDOWN => {
+            update_target();
+            PICKER_STATE.with(|state| {
+                if let Some(state) = state.borrow_mut().as_mut() {
+                    state.picked = state.target;
+                }
+            });
+            cancel(hwnd);
+            LRESULT(0)
+        },
+        WM_RBUTTONDOWN => {
+            cancel(hwnd);
+            LRESULT(0)
+        },
+        WM_KEYDOWN if wparam.0 == VK_ESCAPE.0 as usize => {
+            cancel(hwnd);
+            LRESULT(0)
+        },
+        WM_ACTIVATE if (wparam.0 & 0xFFFF) as u32 == WA_INACTIVE => {
+            cancel(hwnd);
+            LRESULT(0)
+        },
+        WM_DESTROY => {
+            unsafe { PostQuitMessage(0) };
+            LRESULT(0)
+        },
+        _ => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+    }
+}
@@ -0,0 +1,280 @@
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{LazyLock, Mutex},
+};
+use windows::{
+    Win32::{
+        Foundation::{CloseHandle, FILETIME, HANDLE},
+        Security::{GetTokenInformation, TOKEN_ELEVATION, TOKEN_QUERY, TokenElevation},
+        Storage::FileSystem::{GetFileVersionInfoSizeW, GetFileVersionInfoW, VerQueryValueW},
+        System::{
+            Diagnostics::ToolHelp::{
+                CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW,
+                TH32CS_SNAPPROCESS,
+            },
+            Threading::{
+                GetCurrentProcessId, GetProcessTimes, OpenProcess, OpenProcessToken,
+                PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION, QueryFullProcessImageNameW,
+            },
+        },
+    },
+    core::{HSTRING, PWSTR},
+};
+
+static IS_ELEVATED: LazyLock<bool> =
+    LazyLock::new(|| elevation(unsafe { GetCurrentProcessId() }) == Elevation::Elevated);
+
+static VERSION_CACHE: LazyLock<Mutex<HashMap<String, VersionInfo>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static DETAILS_CACHE: LazyLock<Mutex<HashMap<u32, ProcessDetails>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Elevation
+{
+    #[default]
+    Unknown,
+    Standard,
+    Elevated,
+}
+
+impl Elevation
+{
+    pub fn label(self) -> &'static str
+    {
+        match self {
+            Self::Unknown => "Unknown (access denied)",
+            Self::Standard => "Standard",
+            Self::Elevated => "Elevated",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionInfo
+{
+    pub product_name: Option<String>,
+    pub product_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessDetails
+{
+    pub image_path: Option<String>,
+    pub elevation: Elevation,
+}
+
+struct OwnedHandle(HANDLE);
+
+impl OwnedHandle
+{
+    fn open_process(process_id: u32) -> Option<Self>
+    {
+        unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id) }.ok().map(Self)
+    }
+}
+
+impl Drop for OwnedHandle
+{
+    fn drop(&mut self)
+    {
+        unsafe {
+            let _ = CloseHandle(self.0);
+        }
+    }
+}
+
+pub fn image_path(process_id: u32) -> Option<String>
+{
+    let process = OwnedHandle::open_process(process_id)?;
+    let mut buffer = [0u16; 1024];
+    let mut size = buffer.len() as u32;
+
+    unsafe {
+        QueryFullProcessImageNameW(
+            process.0,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut size,
+        )
+        .ok()?;
+    }
+
+    Some(String::from_utf16_lossy(&buffer[..size as usize]))
+}
+
+pub fn creation_time(process_id: u32) -> Option<u64>
+{
+    let process = OwnedHandle::open_process(process_id)?;
+    let mut created = FILETIME::default();
+    let mut exited = FILETIME::default();
+    let mut kernel = FILETIME::default();
+    let mut user = FILETIME::default();
+
+    unsafe {
+        GetProcessTimes(process.0, &mut created, &mut exited, &mut kernel, &mut user).ok()?;
+    }
+
+    Some(((created.dwHighDateTime as u64) << 32) | created.dwLowDateTime as u64)
+}
+
+pub fn list_processes() -> Vec<(u32, u32)>
+{
+    let mut processes = Vec::new();
+
+    unsafe {
+        let Ok(snapshot) = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) else {
+            return processes;
+        };
+        let snapshot = OwnedHandle(snapshot);
+
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+
+        if Process32FirstW(snapshot.0, &mut entry).is_ok() {
+            loop {
+                processes.push((entry.th32ProcessID, entry.th32ParentProcessID));
+
+                if Process32NextW(snapshot.0, &mut entry).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    processes
+}
+
+pub fn elevation(process_id: u32) -> Elevation
+{
+    let Some(process) = OwnedHandle::open_process(process_id) else {
+        return Elevation::Unknown;
+    };
+
+    unsafe {
+        let mut token = HANDLE::default();
+        if OpenProcessToken(process.0, TOKEN_QUERY, &mut token).is_err() {
+            return Elevation::Unknown;
+        }
+        let token = OwnedHandle(token);
+
+        let mut info = TOKEN_ELEVATION::default();
+        let mut returned = 0u32;
+        let result = GetTokenInformation(
+            token.0,
+            TokenElevation,
+            Some(&mut info as *mut TOKEN_ELEVATION as *mut std::ffi::c_void),
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut returned,
+        );
+
+        match result {
+            Ok(()) if info.TokenIsElevated != 0 => Elevation::Elevated,
+            Ok(()) => Elevation::Standard,
+            Err(_) => Elevation::Unknown,
+        }
+    }
+}
+
+pub fn is_elevated() -> bool
+{
+    *IS_ELEVATED
+}
+
+// Every refresh visits each window, and most belong to a handful of processes, so their path and
+// elevation are read once per process id and forgotten when the process exits.
+pub fn details(process_id: u32) -> ProcessDetails
+{
+    if let Some(details) = DETAILS_CACHE.lock().unwrap().get(&process_id) {
+        return details.clone();
+    }
+
+    let details =
+        ProcessDetails { image_path: image_path(process_id), elevation: elevation(process_id) };
+    DETAILS_CACHE.lock().unwrap().insert(process_id, details.clone());
+
+    details
+}
+
+pub fn forget_exited(processes: &[(u32, u32)])
+{
+    let running: HashSet<u32> = processes.iter().map(|(id, _)| *id).collect();
+    DETAILS_CACHE.lock().unwrap().retain(|id, _| running.contains(id));
+}
+
+pub fn version_info(path: &str) -> VersionInfo
+{
+    if let Some(info) = VERSION_CACHE.lock().unwrap().get(path) {
+        return info.clone();
+    }
+
+    let info = read_version_info(path).unwrap_or_default();
+    VERSION_CACHE.lock().unwrap().insert(path.to_string(), info.clone());
+
+    info
+}
+
+fn read_version_info(path: &str) -> Option<VersionInfo>
+{
+    let path = HSTRING::from(path);
+
+    unsafe {
+        let size = GetFileVersionInfoSizeW(&path, None);
+        if size == 0 {
+            return None;
+        }
+
+        let mut data = vec![0u8; size as usize];
+        GetFileVersionInfoW(&path, None, size, data.as_mut_ptr() as *mut std::ffi::c_void).ok()?;
+
+        let mut translation = std::ptr::null_mut();
+        let mut translation_len = 0u32;
+        let (language, code_page) = if VerQueryValueW(
+            data.as_ptr() as *const std::ffi::c_void,
+            &HSTRING::from("\\VarFileInfo\\Translation"),
+            &mut translation,
+            &mut translation_len,
+        )
+        .as_bool()
+            && translation_len >= 4
+        {
+            let pair = translation as *const u16;
+            (*pair, *pair.add(1))
+        } else {
+            (0x0409, 0x04B0)
+        };
+
+        let query = |name: &str| -> Option<String> {
+            let key = format!("\\StringFileInfo\\{:04x}{:04x}\\{}", language, code_page, name);
+            let mut value = std::ptr::null_mut();
+            let mut value_len = 0u32;
+
+            if !VerQueryValueW(
+                data.as_ptr() as *const std::ffi::c_void,
+                &HSTRING::from(key),
+                &mut value,
+                &mut value_len,
+            )
+            .as_bool()
+                || value_len == 0
+            {
+                return None;
+            }
+
+            let chars = std::slice::from_raw_parts(value as *const u16, value_len as usize);
+            let text = String::from_utf16_lossy(chars).trim_end_matches('\0').trim().to_string();
+
+            (!text.is_empty()).then_some(text)
+        };
+
+        Some(VersionInfo {
+            product_name: query("ProductName"),
+            product_version: query("ProductVersion"),
+        })
+    }
+}
@@ -0,0 +1,170 @@
+use crate::config::Config;
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+use windows::{
+    Win32::{
+        Foundation::HWND,
+        Graphics::Gdi::{MONITOR_DEFAULTTONEAREST, MonitorFromWindow},
+        UI::WindowsAndMessaging::{
+            FindWindowExW, FindWindowW, GetForegroundWindow, IsWindow, SW_HIDE, SW_SHOWNA,
+            ShowWindow,
+        },
+    },
+    core::{PCWSTR, w},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+static STATE: LazyLock<Mutex<TaskbarState>> = LazyLock::new(|| Mutex::new(TaskbarState::default()));
+
+#[derive(Default)]
+struct TaskbarState
+{
+    watched: HashSet<isize>,
+    hidden: Vec<isize>,
+    running: bool,
+}
+
+pub fn watch(hwnd: isize)
+{
+    let mut state = STATE.lock().unwrap();
+    state.watched.insert(hwnd);
+
+    if !state.running {
+        state.running = true;
+        std::thread::spawn(run);
+    }
+}
+
+pub fn unwatch(hwnd: isize)
+{
+    let mut state = STATE.lock().unwrap();
+    if state.watched.remove(&hwnd) {
+        update(&mut state);
+    }
+}
+
+pub fn restore()
+{
+    let mut state = STATE.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    state.watched.clear();
+    update(&mut state);
+}
+
+pub fn recover()
+{
+    let Some(marker) = marker_path().filter(|marker| marker.exists()) else {
+        return;
+    };
+
+    for taskbar in taskbars() {
+        unsafe {
+            let _ = ShowWindow(taskbar, SW_SHOWNA);
+        }
+    }
+
+    let _ = std::fs::remove_file(marker);
+}
+
+fn run()
+{
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+
+        let mut state = STATE.lock().unwrap();
+        update(&mut state);
+
+        if state.watched.is_empty() && state.hidden.is_empty() {
+            state.running = false;
+            break;
+        }
+    }
+}
+
+fn update(state: &mut TaskbarState)
+{
+    state.watched.retain(|hwnd| unsafe { IsWindow(Some(to_hwnd(*hwnd))) }.as_bool());
+
+    let foreground = unsafe { GetForegroundWindow() }.0 as isize;
+    let target = if state.watched.contains(&foreground) { taskbar_for(foreground) } else { None };
+
+    state.hidden.retain(|taskbar| {
+        if Some(*taskbar) == target {
+            return true;
+        }
+
+        unsafe {
+            let _ = ShowWindow(to_hwnd(*taskbar), SW_SHOWNA);
+        }
+        false
+    });
+
+    if let Some(taskbar) = target.filter(|taskbar| !state.hidden.contains(taskbar)) {
+        write_marker();
+        unsafe {
+            let _ = ShowWindow(to_hwnd(taskbar), SW_HIDE);
+        }
+        state.hidden.push(taskbar);
+    }
+
+    if state.hidden.is_empty()
+        && let Some(marker) = marker_path()
+    {
+        let _ = std::fs::remove_file(marker);
+    }
+}
+
+fn taskbar_for(hwnd: isize) -> Option<isize>
+{
+    let monitor = unsafe { MonitorFromWindow(to_hwnd(hwnd), MONITOR_DEFAULTTONEAREST) };
+
+    taskbars()
+        .into_iter()
+        .find(|taskbar| unsafe { MonitorFromWindow(*taskbar, MONITOR_DEFAULTTONEAREST) } == monitor)
+        .map(|taskbar| taskbar.0 as isize)
+}
+
+fn taskbars() -> Vec<HWND>
+{
+    let mut taskbars = Vec::new();
+
+    unsafe {
+        if let Ok(primary) = FindWindowW(w!("Shell_TrayWnd"), PCWSTR::null()) {
+            taskbars.push(primary);
+        }
+
+        let mut after = None;
+        while let Ok(secondary) =
+            FindWindowExW(None, after, w!("Shell_SecondaryTrayWnd"), PCWSTR::null())
+        {
+            taskbars.push(secondary);
+            after = Some(secondary);
+        }
+    }
+
+    taskbars
+}
+
+fn marker_path() -> Option<PathBuf>
+{
+    Config::dir().map(|dir| dir.join("taskbar-hidden"))
+}
+
+fn write_marker()
+{
+    if let Some(marker) = marker_path() {
+        if let Some(dir) = marker.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let _ = std::fs::write(marker, "");
+    }
+}
+
+fn to_hwnd(hwnd: isize) -> HWND
+{
+    HWND(hwnd as *mut std::ffi::c_void)
+}
@@ -0,0 +1,409 @@
+use crate::{
+    app,
+    config::Config,
+    instance, logging,
+    window_manager::{self, WindowManager},
+};
+use eframe::egui;
+use std::{
+    cell::RefCell,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU32, Ordering},
+    },
+    thread::JoinHandle,
+};
+use windows::{
+    Win32::{
+        Foundation::{HWND, LPARAM, LRESULT, POINT, WPARAM},
+        System::LibraryLoader::GetModuleHandleW,
+        UI::{
+            Shell::{
+                NIF_ICON, NIF_MESSAGE, NIF_TIP, NIM_ADD, NIM_DELETE, NOTIFYICONDATAW,
+                Shell_NotifyIconW,
+            },
+            WindowsAndMessaging::{
+                AppendMenuW, CreateIcon, CreatePopupMenu, CreateWindowExW, DefWindowProcW,
+                DestroyIcon, DestroyMenu, DestroyWindow, DispatchMessageW, GetCursorPos,
+                GetMessageW, GetSystemMetrics, HICON, HMENU, MF_CHECKED, MF_GRAYED, MF_POPUP,
+                MF_SEPARATOR, MF_STRING, MSG, PostMessageW, PostQuitMessage, RegisterClassW,
+                RegisterWindowMessageW, SM_CXSMICON, SetForegroundWindow, TPM_BOTTOMALIGN,
+                TPM_RETURNCMD, TPM_RIGHTBUTTON, TrackPopupMenu, TranslateMessage, WINDOW_EX_STYLE,
+                WM_APP, WM_CLOSE, WM_DESTROY, WM_LBUTTONDBLCLK, WM_NULL, WM_RBUTTONUP, WNDCLASSW,
+                WS_OVERLAPPED,
+            },
+        },
+    },
+    core::{HSTRING, PCWSTR, w},
+};
+
+const WM_TRAY_ICON: u32 = WM_APP + 1;
+const TRAY_ICON_ID: u32 = 1;
+
+static TASKBAR_CREATED: AtomicU32 = AtomicU32::new(0);
+
+thread_local! {
+    static TRAY_STATE: RefCell<Option<TrayState>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone)]
+struct TrayState
+{
+    window_manager: WindowManager,
+    config: Arc<Mutex<Config>>,
+    egui_ctx: egui::Context,
+    icon: isize,
+}
+
+enum MenuAction
+{
+    Show,
+    Quit,
+    ToggleWindow(isize),
+    ApplyProfile(String),
+}
+
+pub struct Tray
+{
+    hwnd: isize,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Tray
+{
+    pub fn start(
+        window_manager: WindowManager,
+        config: Arc<Mutex<Config>>,
+        egui_ctx: egui::Context,
+    ) -> anyhow::Result<Self>
+    {
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        let thread = std::thread::spawn(move || {
+            let hwnd = match create_tray_window() {
+                Ok(hwnd) => hwnd,
+                Err(e) => {
+                    let _ = sender.send(Err(e));
+                    return;
+                },
+            };
+
+            let icon = create_icon(&app::load_icon()).map(|icon| icon.0 as isize).unwrap_or(0);
+            let state = TrayState { window_manager, config, egui_ctx, icon };
+
+            add_tray_icon(hwnd, &state);
+            TRAY_STATE.with(|tray_state| *tray_state.borrow_mut() = Some(state));
+
+            let _ = sender.send(Ok(hwnd.0 as isize));
+
+            unsafe {
+                let mut msg = MSG::default();
+                while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+                    let _ = TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                }
+
+                if icon != 0 {
+                    let _ = DestroyIcon(HICON(icon as *mut std::ffi::c_void));
+                }
+            }
+        });
+
+        let hwnd = receiver.recv()??;
+
+        Ok(Self { hwnd, thread: Some(thread) })
+    }
+}
+
+impl Drop for Tray
+{
+    fn drop(&mut self)
+    {
+        unsafe {
+            let _ = PostMessageW(
+                Some(HWND(self.hwnd as *mut std::ffi::c_void)),
+                WM_CLOSE,
+                WPARAM(0),
+                LPARAM(0),
+            );
+        }
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn create_tray_window() -> anyhow::Result<HWND>
+{
+    unsafe {
+        let instance = GetModuleHandleW(PCWSTR::null())?;
+        let class_name = w!("ihateborders_tray");
+
+        let window_class = WNDCLASSW {
+            lpfnWndProc: Some(tray_window_proc),
+            hInstance: instance.into(),
+            lpszClassName: class_name,
+            ..Default::default()
+        };
+        RegisterClassW(&window_class);
+
+        TASKBAR_CREATED.store(RegisterWindowMessageW(w!("TaskbarCreated")), Ordering::Relaxed);
+
+        let hwnd = CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            class_name,
+            w!("ihateborders tray"),
+            WS_OVERLAPPED,
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            Some(instance.into()),
+            None,
+        )?;
+
+        Ok(hwnd)
+    }
+}
+
+fn create_icon(icon_data: &egui::IconData) -> anyhow::Result<HICON>
+{
+    let size = unsafe { GetSystemMetrics(SM_CXSMICON) }.max(16) as u32;
+
+    let image =
+        image::RgbaImage::from_raw(icon_data.width, icon_data.height, icon_data.rgba.clone())
+            .ok_or_else(|| anyhow::anyhow!("Invalid icon data"))?;
+    let mut bgra =
+        image::imageops::resize(&image, size, size, image::imageops::FilterType::Triangle)
+            .into_raw();
+
+    for chunk in bgra.chunks_exact_mut(4) {
+        chunk.swap(0, 2);
+    }
+
+    let mask_stride = size.div_ceil(16) * 2;
+    let mask = vec![0u8; (mask_stride * size) as usize];
+
+    let icon =
+        unsafe { CreateIcon(None, size as i32, size as i32, 1, 32, mask.as_ptr(), bgra.as_ptr())? };
+
+    Ok(icon)
+}
+
+fn notify_icon_data(hwnd: HWND, state: &TrayState) -> NOTIFYICONDATAW
+{
+    let mut data = NOTIFYICONDATAW {
+        cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as u32,
+        hWnd: hwnd,
+        uID: TRAY_ICON_ID,
+        uFlags: NIF_ICON | NIF_MESSAGE | NIF_TIP,
+        uCallbackMessage: WM_TRAY_ICON,
+        hIcon: HICON(state.icon as *mut std::ffi::c_void),
+        ..Default::default()
+    };
+
+    for (dst, src) in data.szTip.iter_mut().zip("ihateborders".encode_utf16()) {
+        *dst = src;
+    }
+
+    data
+}
+
+fn add_tray_icon(hwnd: HWND, state: &TrayState)
+{
+    let data = notify_icon_data(hwnd, state);
+    unsafe {
+        let _ = Shell_NotifyIconW(NIM_ADD, &data);
+    }
+}
+
+fn remove_tray_icon(hwnd: HWND)
+{
+    let data = NOTIFYICONDATAW {
+        cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as u32,
+        hWnd: hwnd,
+        uID: TRAY_ICON_ID,
+        ..Default::default()
+    };
+    unsafe {
+        let _ = Shell_NotifyIconW(NIM_DELETE, &data);
+    }
+}
+
+fn tray_state() -> Option<TrayState>
+{
+    TRAY_STATE.with(|state| state.borrow().clone())
+}
+
+unsafe extern "system" fn tray_window_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT
+{
+    unsafe {
+        match msg {
+            WM_TRAY_ICON => {
+                if let Some(state) = tray_state() {
+                    match lparam.0 as u32 {
+                        WM_LBUTTONDBLCLK => instance::show_main_window(&state.egui_ctx),
+                        WM_RBUTTONUP => show_context_menu(hwnd, &state),
+                        _ => {},
+                    }
+                }
+                LRESULT(0)
+            },
+            WM_DESTROY => {
+                remove_tray_icon(hwnd);
+                PostQuitMessage(0);
+                LRESULT(0)
+            },
+            WM_CLOSE => {
+                let _ = DestroyWindow(hwnd);
+                LRESULT(0)
+            },
+            _ if msg == TASKBAR_CREATED.load(Ordering::Relaxed) && msg != 0 => {
+                if let Some(state) = tray_state() {
+                    add_tray_icon(hwnd, &state);
+                }
+                LRESULT(0)
+            },
+            _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+        }
+    }
+}
+
+fn show_context_menu(hwnd: HWND, state: &TrayState)
+{
+    state.window_manager.refresh_windows();
+
+    let mut actions = Vec::new();
+    let menu = match build_menu(state, &mut actions) {
+        Ok(menu) => menu,
+        Err(e) => {
+            logging::error("tray", format!("Failed to build tray menu: {}", e));
+            return;
+        },
+    };
+
+    let selected = unsafe {
+        let mut cursor = POINT::default();
+        let _ = GetCursorPos(&mut cursor);
+
+        let _ = SetForegroundWindow(hwnd);
+        let selected = TrackPopupMenu(
+            menu,
+            TPM_RETURNCMD | TPM_RIGHTBUTTON | TPM_BOTTOMALIGN,
+            cursor.x,
+            cursor.y,
+            None,
+            hwnd,
+            None,
+        );
+        let _ = PostMessageW(Some(hwnd), WM_NULL, WPARAM(0), LPARAM(0));
+        let _ = DestroyMenu(menu);
+
+        selected.0 as usize
+    };
+
+    if selected > 0 && selected <= actions.len() {
+        run_action(state, actions.swap_remove(selected - 1));
+    }
+}
+
+fn build_menu(state: &TrayState, actions: &mut Vec<MenuAction>) -> anyhow::Result<HMENU>
+{
+    let mut append = |menu: HMENU, flags, label: &HSTRING, action| -> anyhow::Result<()> {
+        actions.push(action);
+        unsafe { AppendMenuW(menu, flags, actions.len(), label)? };
+        Ok(())
+    };
+
+    unsafe {
+        let menu = CreatePopupMenu()?;
+        let windows = state.window_manager.get_windows();
+
+        if windows.is_empty() {
+            AppendMenuW(menu, MF_STRING | MF_GRAYED, 0, w!("No windows found"))?;
+        }
+
+        for window in &windows {
+            let status = if window.is_borderless { "[B]" } else { "[W]" };
+            let label = menu_label(&format!("{} {}", status, window.display_text()));
+            let flags = if window.is_borderless { MF_STRING | MF_CHECKED } else { MF_STRING };
+
+            append(menu, flags, &label, MenuAction::ToggleWindow(window.hwnd))?;
+        }
+
+        let profiles = state.config.lock().unwrap().profiles.clone();
+        if !profiles.is_empty() {
+            let profile_menu = CreatePopupMenu()?;
+
+            for profile in profiles {
+                let label = menu_label(&profile.name);
+                append(profile_menu, MF_STRING, &label, MenuAction::ApplyProfile(profile.name))?;
+            }
+
+            AppendMenuW(menu, MF_SEPARATOR, 0, PCWSTR::null())?;
+            AppendMenuW(menu, MF_POPUP, profile_menu.0 as usize, w!("Apply profile"))?;
+        }
+
+        AppendMenuW(menu, MF_SEPARATOR, 0, PCWSTR::null())?;
+        append(menu, MF_STRING, &HSTRING::from("Show ihateborders"), MenuAction::Show)?;
+        append(menu, MF_STRING, &HSTRING::from("Quit"), MenuAction::Quit)?;
+
+        Ok(menu)
+    }
+}
+
+fn menu_label(text: &str) -> HSTRING
+{
+    HSTRING::from(text.replace('&', "&&"))
+}
+
+fn run_action(state: &TrayState, action: MenuAction)
+{
+    let displays = state.window_manager.get_displays();
+
+    match action {
+        MenuAction::Show => instance::show_main_window(&state.egui_ctx),
+        MenuAction::Quit => app::quit(&state.egui_ctx),
+        MenuAction::ToggleWindow(target) => {
+            let window =
+                state.window_manager.get_windows().into_iter().find(|window| window.hwnd == target);
+            let ((resize_to_screen, display, frame_mode), effects) = {
+                let config = state.config.lock().unwrap();
+                (config.placement_for(window.as_ref()), config.effects())
+            };
+            let selected_display = if resize_to_screen {
+                window_manager::find_display(&displays, display.as_deref())
+            } else {
+                None
+            };
+
+            if let Err(e) = state.window_manager.toggle_borderless(
+                target,
+                resize_to_screen,
+                selected_display,
+                frame_mode,
+                effects,
+            ) {
+                logging::warn("tray", format!("Failed to toggle borderless from tray: {}", e));
+            }
+        },
+        MenuAction::ApplyProfile(name) => {
+            let profile = state.config.lock().unwrap().find_profile(&name).cloned();
+            if let Some(profile) = profile
+                && let Err(e) = state.window_manager.apply_profile_to_matching(&profile, &displays)
+            {
+                logging::warn("tray", format!("Failed to apply profile '{}': {}", name, e));
+            }
+        },
+    }
+
+    state.egui_ctx.request_repaint();
+}
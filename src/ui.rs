@@ -1,7 +1,95 @@
-use crate::window_manager::{DisplayInfo, WindowInfo};
+use crate::{
+    config::{ConflictPolicy, Favorite, ImportReport, Profile, WindowFilters},
+    history::HistoryEntry,
+    hotkeys::{HotkeyAction, HotkeyStatus},
+    logging::{LogEntry, LogLevel},
+    window_manager::{
+        self, Anchor, DisplayInfo, FrameMode, SIZE_PRESETS, WindowInfo, WindowModifiers,
+        WindowSize, WindowSort,
+    },
+};
 use egui::{
     Align, Align2, Color32, ColorImage, FontId, Layout, RichText, Sense, Stroke, Style, Visuals,
 };
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::Path,
+    time::Instant,
+};
+
+pub struct WindowListView<'a>
+{
+    pub order: &'a [usize],
+    pub group_by_process: bool,
+    pub favorites: &'a [Favorite],
+}
+
+pub enum SelectorAction
+{
+    ToggleFavorite(usize),
+    RemoveFavorite(usize),
+    Launch(usize),
+}
+
+pub enum HotkeyEdit
+{
+    Rebind(HotkeyAction),
+    Clear(HotkeyAction),
+}
+
+pub enum ProfileAction
+{
+    Export,
+    Import,
+    ImportBorderlessGaming,
+    SetConflict(ConflictPolicy),
+}
+
+pub enum DisplayMove
+{
+    Send(usize),
+    Next,
+    Swap,
+}
+
+pub enum HistoryAction
+{
+    Undo(usize),
+    Redo(usize),
+}
+
+pub struct StatusMessage
+{
+    pub text: String,
+    pub is_error: bool,
+    pub offer_relaunch: bool,
+    pub shown: Instant,
+}
+
+pub enum StatusAction
+{
+    Dismiss,
+    Relaunch,
+}
+
+pub enum TransferStatus
+{
+    Imported(ImportReport),
+    Exported
+    {
+        path: String,
+        count: usize,
+    },
+    Failed(String),
+}
+
+#[derive(Default)]
+pub struct ProfileTransfer
+{
+    pub path: String,
+    pub selected: HashSet<String>,
+    pub status: Option<TransferStatus>,
+}
 
 pub trait IconCacheInterface
 {
@@ -100,21 +188,43 @@ pub fn render_header(ui: &mut egui::Ui, window_count: usize)
     ui.add_space(10.0);
 }
 
-pub fn render_window_selector(
-    ui: &mut egui::Ui,
-    windows: &[WindowInfo],
-    selected_window: &mut Option<usize>,
-    icon_cache: &mut dyn IconCacheInterface,
-)
+pub fn render_selector_header(ui: &mut egui::Ui, picking: bool) -> bool
 {
+    let mut pick_clicked = false;
+
     ui.horizontal(|ui| {
         ui.label(
             RichText::new("Select Window:")
                 .font(FontId::proportional(13.0))
                 .color(Color32::from_gray(200)),
         );
+
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            let text = if picking { "Picking..." } else { "Pick" };
+            let button = egui::Button::new(RichText::new(text).font(FontId::proportional(12.0)));
+
+            if ui
+                .add_enabled(!picking, button)
+                .on_hover_text("Click a window on screen to select it, Escape to cancel")
+                .clicked()
+            {
+                pick_clicked = true;
+            }
+        });
     });
 
+    pick_clicked
+}
+
+pub fn render_window_selector(
+    ui: &mut egui::Ui,
+    windows: &[WindowInfo],
+    selected_window: &mut Option<usize>,
+    icon_cache: &mut dyn IconCacheInterface,
+    hovered_window: &mut Option<isize>,
+    view: &WindowListView<'_>,
+) -> Option<SelectorAction>
+{
     ui.add_space(5.0);
 
     let selected_text = if let Some(index) = selected_window {
@@ -127,186 +237,1390 @@ pub fn render_window_selector(
         "Select a window...".to_string()
     };
 
+    let mut action = None;
+    let mut toggled_favorite = None;
+
     egui::ComboBox::from_id_salt("window_selector")
         .selected_text(selected_text)
         .width(ui.available_width())
         .height(150.0)
+        .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
         .show_ui(ui, |ui| {
             ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
 
-            for (index, window) in windows.iter().enumerate() {
-                ui.horizontal(|ui| {
-                    ui.set_min_width(ui.available_width());
-                    if let Some(icon_data) = &window.icon_data {
-                        let cache_key = format!("icon_{}", window.hwnd);
-
-                        if !icon_cache.contains_key(&cache_key) {
-                            let color_image =
-                                ColorImage::from_rgba_unmultiplied([16, 16], icon_data);
-                            let texture = ui.ctx().load_texture(
-                                &cache_key,
-                                color_image,
-                                egui::TextureOptions::LINEAR,
-                            );
-                            icon_cache.insert(cache_key.clone(), texture);
-                        }
+            let mut row = |ui: &mut egui::Ui, index: usize, is_favorite: bool| {
+                if render_window_row(
+                    ui,
+                    index,
+                    &windows[index],
+                    is_favorite,
+                    selected_window,
+                    icon_cache,
+                    hovered_window,
+                ) {
+                    toggled_favorite = Some(index);
+                }
+            };
 
-                        if let Some(texture) = icon_cache.get(&cache_key) {
-                            ui.image((texture.id(), egui::vec2(16.0, 16.0)));
-                        }
-                    } else {
-                        let (rect, _response) =
-                            ui.allocate_exact_size(egui::vec2(16.0, 16.0), Sense::hover());
-
-                        let status_icon = "○";
-                        let status_color = if window.is_borderless {
-                            Color32::from_rgb(100, 200, 100)
-                        } else {
-                            Color32::from_rgb(180, 180, 180)
-                        };
-
-                        ui.painter().text(
-                            rect.center(),
-                            Align2::CENTER_CENTER,
-                            status_icon,
-                            FontId::proportional(12.0),
-                            status_color,
-                        );
-                    }
+            let is_pinned = |window: &WindowInfo| {
+                view.favorites.iter().any(|favorite| favorite.matches(window))
+            };
 
-                    let status_text = if window.is_borderless { "[B]" } else { "[W]" };
-                    let status_color = if window.is_borderless {
-                        Color32::from_rgb(100, 200, 100)
-                    } else {
-                        Color32::from_rgb(150, 150, 150)
-                    };
+            for (favorite_index, favorite) in view.favorites.iter().enumerate() {
+                let running: Vec<usize> = view
+                    .order
+                    .iter()
+                    .copied()
+                    .filter(|&index| favorite.matches(&windows[index]))
+                    .collect();
 
-                    ui.label(
-                        RichText::new(status_text)
-                            .color(status_color)
-                            .font(FontId::proportional(10.0)),
-                    );
+                if running.is_empty()
+                    && let Some(favorite_action) =
+                        render_missing_favorite(ui, favorite_index, favorite)
+                {
+                    action = Some(favorite_action);
+                }
+
+                for index in running {
+                    row(ui, index, true);
+                }
+            }
+
+            if !view.favorites.is_empty() {
+                ui.separator();
+            }
+
+            let order: Vec<usize> =
+                view.order.iter().copied().filter(|&index| !is_pinned(&windows[index])).collect();
 
-                    ui.allocate_ui_with_layout(
-                        egui::vec2(ui.available_width(), ui.spacing().interact_size.y),
-                        Layout::left_to_right(Align::Center),
-                        |ui| {
-                            let response = ui.selectable_label(
-                                *selected_window == Some(index),
-                                window.display_text(),
-                            );
-                            if response.clicked() {
-                                *selected_window = Some(index);
+            if view.group_by_process {
+                for (process_name, indices) in group_by_process_name(windows, &order) {
+                    if indices.len() == 1 {
+                        row(ui, indices[0], false);
+                        continue;
+                    }
+
+                    egui::CollapsingHeader::new(format!("{} ({})", process_name, indices.len()))
+                        .id_salt(("process_group", process_name))
+                        .show(ui, |ui| {
+                            for index in indices {
+                                row(ui, index, false);
                             }
-                        },
-                    );
-                });
+                        });
+                }
+            } else {
+                for index in order {
+                    row(ui, index, false);
+                }
             }
         });
+
+    toggled_favorite.map(SelectorAction::ToggleFavorite).or(action)
 }
 
-pub fn render_position_checkbox(ui: &mut egui::Ui, resize_to_screen: &mut bool)
+fn group_by_process_name<'a>(
+    windows: &'a [WindowInfo],
+    order: &[usize],
+) -> Vec<(&'a str, Vec<usize>)>
 {
-    ui.add_space(10.0);
+    let mut groups: Vec<(&str, Vec<usize>)> = Vec::new();
+
+    for &index in order {
+        let process_name = windows[index].process_name.as_str();
+        match groups.iter_mut().find(|(name, _)| name.eq_ignore_ascii_case(process_name)) {
+            Some((_, indices)) => indices.push(index),
+            None => groups.push((process_name, vec![index])),
+        }
+    }
+
+    groups
+}
+
+fn render_star(ui: &mut egui::Ui, is_favorite: bool) -> bool
+{
+    let (text, color, hint) = if is_favorite {
+        ("★", Color32::from_rgb(230, 190, 60), "Unpin")
+    } else {
+        ("☆", Color32::from_gray(120), "Pin to top")
+    };
+
+    ui.add(egui::Label::new(RichText::new(text).color(color)).sense(Sense::click()))
+        .on_hover_text(hint)
+        .clicked()
+}
+
+fn render_missing_favorite(
+    ui: &mut egui::Ui,
+    favorite_index: usize,
+    favorite: &Favorite,
+) -> Option<SelectorAction>
+{
+    let mut action = None;
 
     ui.horizontal(|ui| {
-        ui.add_space(5.0);
+        ui.set_min_width(ui.available_width());
 
-        let checkbox = egui::Checkbox::new(resize_to_screen, "");
+        if render_star(ui, true) {
+            action = Some(SelectorAction::RemoveFavorite(favorite_index));
+        }
 
-        ui.add(checkbox);
+        let name = match &favorite.title_contains {
+            Some(pattern) if !pattern.is_empty() => {
+                format!("{} - {}", favorite.process_name, pattern)
+            },
+            _ => favorite.process_name.clone(),
+        };
 
         ui.label(
-            RichText::new("Resize to screen")
+            RichText::new(format!("{} (not running)", name))
                 .font(FontId::proportional(12.0))
-                .color(Color32::from_gray(180)),
+                .color(Color32::from_gray(110)),
         );
+
+        if let Some(exe_path) = &favorite.exe_path {
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                if ui.small_button("Launch").on_hover_text(exe_path).clicked() {
+                    action = Some(SelectorAction::Launch(favorite_index));
+                    ui.close_kind(egui::UiKind::Popup);
+                }
+            });
+        }
     });
+
+    action
 }
 
-pub fn render_display_selector(
+fn render_window_row(
     ui: &mut egui::Ui,
-    displays: &[DisplayInfo],
-    selected_display: &mut Option<usize>,
-)
+    index: usize,
+    window: &WindowInfo,
+    is_favorite: bool,
+    selected_window: &mut Option<usize>,
+    icon_cache: &mut dyn IconCacheInterface,
+    hovered_window: &mut Option<isize>,
+) -> bool
+{
+    let mut star_clicked = false;
+
+    ui.horizontal(|ui| {
+        ui.set_min_width(ui.available_width());
+
+        if render_star(ui, is_favorite) {
+            star_clicked = true;
+        }
+
+        if let Some(icon_data) = &window.icon_data {
+            let cache_key = format!("icon_{}", window.hwnd);
+
+            if !icon_cache.contains_key(&cache_key) {
+                let color_image = ColorImage::from_rgba_unmultiplied([16, 16], icon_data);
+                let texture =
+                    ui.ctx().load_texture(&cache_key, color_image, egui::TextureOptions::LINEAR);
+                icon_cache.insert(cache_key.clone(), texture);
+            }
+
+            if let Some(texture) = icon_cache.get(&cache_key) {
+                ui.image((texture.id(), egui::vec2(16.0, 16.0)));
+            }
+        } else {
+            let (rect, _response) = ui.allocate_exact_size(egui::vec2(16.0, 16.0), Sense::hover());
+
+            let status_icon = "○";
+            let status_color = if window.is_borderless {
+                Color32::from_rgb(100, 200, 100)
+            } else {
+                Color32::from_rgb(180, 180, 180)
+            };
+
+            ui.painter().text(
+                rect.center(),
+                Align2::CENTER_CENTER,
+                status_icon,
+                FontId::proportional(12.0),
+                status_color,
+            );
+        }
+
+        let status_text = if window.is_borderless { "[B]" } else { "[W]" };
+        let status_color = if window.is_borderless {
+            Color32::from_rgb(100, 200, 100)
+        } else {
+            Color32::from_rgb(150, 150, 150)
+        };
+
+        ui.label(RichText::new(status_text).color(status_color).font(FontId::proportional(10.0)));
+
+        if window.requires_elevation() {
+            ui.label(
+                RichText::new("🔒")
+                    .color(Color32::from_rgb(230, 180, 80))
+                    .font(FontId::proportional(10.0)),
+            )
+            .on_hover_text(
+                "Runs as administrator; ihateborders must run as administrator too to change it",
+            );
+        }
+
+        for badge in window.modifiers.badges() {
+            ui.label(
+                RichText::new(badge)
+                    .color(Color32::from_rgb(120, 170, 230))
+                    .font(FontId::proportional(10.0)),
+            );
+        }
+
+        ui.allocate_ui_with_layout(
+            egui::vec2(ui.available_width(), ui.spacing().interact_size.y),
+            Layout::left_to_right(Align::Center),
+            |ui| {
+                let response =
+                    ui.selectable_label(*selected_window == Some(index), window.display_text());
+                let response = response.on_hover_text(window.tooltip_text());
+                if response.clicked() {
+                    *selected_window = Some(index);
+                    ui.close_kind(egui::UiKind::Popup);
+                }
+                if response.hovered() {
+                    *hovered_window = Some(window.hwnd);
+                }
+            },
+        );
+    });
+
+    star_clicked
+}
+
+pub fn render_window_details(ui: &mut egui::Ui, window: &WindowInfo)
 {
     ui.add_space(5.0);
 
+    egui::CollapsingHeader::new(
+        RichText::new("Window Details")
+            .font(FontId::proportional(12.0))
+            .color(Color32::from_gray(180)),
+    )
+    .id_salt("window_details")
+    .show(ui, |ui| {
+        let yes_no = |value: bool| if value { "Yes" } else { "No" }.to_string();
+
+        let rows = [
+            ("Handle", format!("0x{:X}", window.hwnd)),
+            ("Process ID", window.process_id.to_string()),
+            ("Thread ID", window.thread_id.to_string()),
+            ("Class", window.class_name.clone()),
+            ("Executable", window.exe_path.clone().unwrap_or_else(|| "Unknown".to_string())),
+            ("Product", window.product_name.clone().unwrap_or_else(|| "Unknown".to_string())),
+            ("Version", window.product_version.clone().unwrap_or_else(|| "Unknown".to_string())),
+            ("Elevation", window.elevation.label().to_string()),
+            ("Rect", window.rect.to_string()),
+            ("Client rect", window.client_rect.to_string()),
+            ("Monitor", window.monitor.clone().unwrap_or_else(|| "Unknown".to_string())),
+            ("Style", format!("0x{:08X}", window.style)),
+            ("", window_manager::style_flags(window.style).join(" | ")),
+            ("Extended style", format!("0x{:08X}", window.ex_style)),
+            ("", window_manager::ex_style_flags(window.ex_style).join(" | ")),
+            ("Owned", yes_no(window.is_owned)),
+            ("Cloaked", yes_no(window.is_cloaked)),
+            ("Minimized", yes_no(window.is_minimized)),
+            ("Maximized", yes_no(window.is_maximized)),
+        ];
+
+        egui::Grid::new("window_details_grid").num_columns(2).spacing([10.0, 2.0]).show(ui, |ui| {
+            for (name, value) in rows {
+                ui.label(
+                    RichText::new(name)
+                        .font(FontId::proportional(11.0))
+                        .color(Color32::from_gray(150)),
+                );
+                ui.add(
+                    egui::Label::new(
+                        RichText::new(value)
+                            .font(FontId::monospace(11.0))
+                            .color(Color32::from_gray(200)),
+                    )
+                    .wrap(),
+                );
+                ui.end_row();
+            }
+        });
+    });
+}
+
+pub fn render_list_options(
+    ui: &mut egui::Ui,
+    sort: &mut WindowSort,
+    group_by_process: &mut bool,
+) -> bool
+{
+    let mut changed = false;
+
     ui.horizontal(|ui| {
-        ui.add_space(5.0);
+        ui.label(
+            RichText::new("Sort:").font(FontId::proportional(12.0)).color(Color32::from_gray(180)),
+        );
+
+        egui::ComboBox::from_id_salt("window_sort").selected_text(sort.label()).show_ui(ui, |ui| {
+            for option in WindowSort::ALL {
+                changed |= ui.selectable_value(sort, option, option.label()).changed();
+            }
+        });
+
+        ui.add_space(10.0);
 
+        changed |= ui.add(egui::Checkbox::new(group_by_process, "")).changed();
         ui.label(
-            RichText::new("Display:")
+            RichText::new("Group by process")
                 .font(FontId::proportional(12.0))
                 .color(Color32::from_gray(180)),
         );
     });
 
-    ui.add_space(3.0);
+    ui.add_space(5.0);
 
-    let selected_text = if let Some(index) = selected_display {
-        if let Some(display) = displays.get(*index) {
-            display.display_text()
-        } else {
-            "Select a display...".to_string()
-        }
-    } else {
-        "Select a display...".to_string()
-    };
+    changed
+}
+
+pub fn render_position_checkbox(ui: &mut egui::Ui, resize_to_screen: &mut bool)
+{
+    ui.add_space(10.0);
 
     ui.horizontal(|ui| {
         ui.add_space(5.0);
 
-        egui::ComboBox::from_id_salt("display_selector")
-            .selected_text(selected_text)
-            .width(ui.available_width() - 10.0)
-            .show_ui(ui, |ui| {
-                for (index, display) in displays.iter().enumerate() {
-                    let response = ui
-                        .selectable_label(*selected_display == Some(index), display.display_text());
-                    if response.clicked() {
-                        *selected_display = Some(index);
-                    }
-                }
-            });
+        let checkbox = egui::Checkbox::new(resize_to_screen, "");
+
+        ui.add(checkbox);
+
+        ui.label(
+            RichText::new("Resize to screen")
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_gray(180)),
+        );
     });
 }
 
-pub fn render_action_button(
+pub fn render_size_selector(
     ui: &mut egui::Ui,
-    windows: &[WindowInfo],
-    selected_window: Option<usize>,
-) -> Option<usize>
+    window_size: &mut Option<WindowSize>,
+    custom_sizes: &mut Vec<(i32, i32)>,
+    new_size: &mut String,
+) -> bool
 {
-    ui.add_space(15.0);
+    let mut changed = false;
+    let anchor = window_size.map(|size| size.anchor).unwrap_or_default();
+    let size_of = |(width, height): (i32, i32)| Some(WindowSize { width, height, anchor });
 
-    let mut clicked_window = None;
+    ui.horizontal(|ui| {
+        ui.add_space(5.0);
+        ui.label(
+            RichText::new("Size").font(FontId::proportional(12.0)).color(Color32::from_gray(180)),
+        );
 
-    let button_enabled = selected_window.is_some();
-    let button_text = if let Some(index) = selected_window {
-        if let Some(window) = windows.get(index) {
-            if window.is_borderless { "Restore Borders" } else { "Make Borderless" }
-        } else {
-            "Make Borderless"
-        }
-    } else {
-        "Make Borderless"
-    };
+        let selected_text = match window_size {
+            Some(size) => size.label(),
+            None => "Fill screen".to_string(),
+        };
 
-    ui.with_layout(Layout::top_down(Align::Center), |ui| {
-        ui.add_enabled_ui(button_enabled, |ui| {
-            let button = egui::Button::new(
-                RichText::new(button_text).font(FontId::proportional(14.0)).color(
-                    if button_enabled { Color32::from_gray(255) } else { Color32::from_gray(120) },
-                ),
-            )
-            .min_size(egui::vec2(180.0, 35.0));
+        egui::ComboBox::from_id_salt("window_size")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                changed |= ui.selectable_value(window_size, None, "Fill screen").changed();
 
-            if ui.add(button).clicked() && button_enabled {
-                clicked_window = selected_window;
-            }
+                for (width, height, aspect) in SIZE_PRESETS {
+                    let label = format!("{}x{} ({})", width, height, aspect);
+                    changed |=
+                        ui.selectable_value(window_size, size_of((width, height)), label).changed();
+                }
+
+                for size in custom_sizes.iter() {
+                    let label = format!("{}x{} (custom)", size.0, size.1);
+                    changed |= ui.selectable_value(window_size, size_of(*size), label).changed();
+                }
+            })
+            .response
+            .on_hover_text("Client area size of the window, placed on the selected display");
+    });
+
+    if let Some(size) = window_size.as_mut() {
+        ui.horizontal(|ui| {
+            ui.add_space(5.0);
+            ui.label(
+                RichText::new("Anchor")
+                    .font(FontId::proportional(12.0))
+                    .color(Color32::from_gray(180)),
+            );
+
+            egui::ComboBox::from_id_salt("size_anchor").selected_text(size.anchor.label()).show_ui(
+                ui,
+                |ui| {
+                    for option in Anchor::ALL {
+                        changed |=
+                            ui.selectable_value(&mut size.anchor, option, option.label()).changed();
+                    }
+                },
+            );
         });
+    }
+
+    ui.horizontal(|ui| {
+        ui.add_space(5.0);
+
+        let parsed = WindowSize::parse(new_size);
+        ui.add(
+            egui::TextEdit::singleline(new_size).hint_text("e.g. 2560x1080").desired_width(100.0),
+        );
+
+        if ui.add_enabled(parsed.is_some(), egui::Button::new("Add size")).clicked()
+            && let Some(size) = parsed
+        {
+            if !custom_sizes.contains(&size) {
+                custom_sizes.push(size);
+            }
+            *window_size = size_of(size);
+            new_size.clear();
+            changed = true;
+        }
+
+        let custom = window_size
+            .map(|size| (size.width, size.height))
+            .filter(|size| custom_sizes.contains(size));
+        if let Some(size) = custom
+            && ui.button("Remove size").clicked()
+        {
+            custom_sizes.retain(|existing| *existing != size);
+            *window_size = None;
+            changed = true;
+        }
     });
 
-    clicked_window
+    changed
+}
+
+pub fn render_frame_mode_selector(ui: &mut egui::Ui, frame_mode: &mut FrameMode) -> bool
+{
+    let mut changed = false;
+
+    ui.add_space(5.0);
+
+    ui.horizontal(|ui| {
+        ui.add_space(5.0);
+        ui.label(
+            RichText::new("Frame").font(FontId::proportional(12.0)).color(Color32::from_gray(180)),
+        );
+
+        egui::ComboBox::from_id_salt("frame_mode").selected_text(frame_mode.label()).show_ui(
+            ui,
+            |ui| {
+                for option in FrameMode::ALL {
+                    changed |= ui.selectable_value(frame_mode, option, option.label()).changed();
+                }
+            },
+        );
+    });
+
+    changed
+}
+
+pub fn render_taskbar_option(ui: &mut egui::Ui, hide_taskbar: &mut bool) -> bool
+{
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.add_space(5.0);
+
+        changed |= ui.add(egui::Checkbox::new(hide_taskbar, "")).changed();
+        ui.label(
+            RichText::new("Hide taskbar while focused")
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_gray(180)),
+        )
+        .on_hover_text(
+            "Hides the taskbar on the window's display while a window made borderless is in front",
+        );
+    });
+
+    changed
+}
+
+pub fn render_cursor_option(ui: &mut egui::Ui, lock_cursor: &mut bool) -> bool
+{
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.add_space(5.0);
+
+        changed |= ui.add(egui::Checkbox::new(lock_cursor, "")).changed();
+        ui.label(
+            RichText::new("Lock cursor while focused")
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_gray(180)),
+        )
+        .on_hover_text(
+            "Keeps the mouse cursor inside a window made borderless while it is in front; Alt+Tab releases it",
+        );
+    });
+
+    changed
+}
+
+pub fn render_backdrop_option(ui: &mut egui::Ui, backdrop: &mut Option<[u8; 3]>) -> bool
+{
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.add_space(5.0);
+
+        let mut enabled = backdrop.is_some();
+        if ui.add(egui::Checkbox::new(&mut enabled, "")).changed() {
+            *backdrop = enabled.then_some([0, 0, 0]);
+            changed = true;
+        }
+        ui.label(
+            RichText::new("Backdrop")
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_gray(180)),
+        )
+        .on_hover_text(
+            "Covers the rest of the display behind a window that does not fill it, such as a sized or letterboxed one",
+        );
+
+        if let Some(color) = backdrop.as_mut() {
+            changed |= ui.color_edit_button_srgb(color).changed();
+        }
+    });
+
+    changed
+}
+
+pub fn render_tray_options(
+    ui: &mut egui::Ui,
+    close_to_tray: &mut bool,
+    start_minimized: &mut bool,
+) -> bool
+{
+    let mut changed = false;
+
+    ui.add_space(5.0);
+
+    ui.horizontal(|ui| {
+        ui.add_space(5.0);
+
+        changed |= ui.add(egui::Checkbox::new(close_to_tray, "")).changed();
+        ui.label(
+            RichText::new("Close to tray")
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_gray(180)),
+        );
+
+        ui.add_space(10.0);
+
+        changed |= ui.add(egui::Checkbox::new(start_minimized, "")).changed();
+        ui.label(
+            RichText::new("Start minimized")
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_gray(180)),
+        );
+    });
+
+    changed
+}
+
+pub fn render_display_selector(
+    ui: &mut egui::Ui,
+    displays: &[DisplayInfo],
+    selected_display: &mut Option<usize>,
+)
+{
+    ui.add_space(5.0);
+
+    ui.horizontal(|ui| {
+        ui.add_space(5.0);
+
+        ui.label(
+            RichText::new("Display:")
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_gray(180)),
+        );
+    });
+
+    ui.add_space(3.0);
+
+    let selected_text = if let Some(index) = selected_display {
+        if let Some(display) = displays.get(*index) {
+            display.display_text()
+        } else {
+            "Select a display...".to_string()
+        }
+    } else {
+        "Select a display...".to_string()
+    };
+
+    ui.horizontal(|ui| {
+        ui.add_space(5.0);
+
+        egui::ComboBox::from_id_salt("display_selector")
+            .selected_text(selected_text)
+            .width(ui.available_width() - 10.0)
+            .show_ui(ui, |ui| {
+                for (index, display) in displays.iter().enumerate() {
+                    let response = ui
+                        .selectable_label(*selected_display == Some(index), display.display_text());
+                    if response.clicked() {
+                        *selected_display = Some(index);
+                    }
+                }
+            });
+    });
+}
+
+pub fn render_display_controls(ui: &mut egui::Ui, displays: &[DisplayInfo]) -> Option<DisplayMove>
+{
+    if displays.len() < 2 {
+        return None;
+    }
+
+    let mut action = None;
+
+    ui.horizontal(|ui| {
+        ui.add_space(5.0);
+        ui.label(
+            RichText::new("Move to")
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_gray(180)),
+        );
+
+        for (index, display) in displays.iter().enumerate() {
+            if ui
+                .small_button((index + 1).to_string())
+                .on_hover_text(display.display_text())
+                .clicked()
+            {
+                action = Some(DisplayMove::Send(index));
+            }
+        }
+
+        if ui.small_button("Next").clicked() {
+            action = Some(DisplayMove::Next);
+        }
+
+        if ui
+            .small_button("Swap")
+            .on_hover_text("Trade places with the borderless window on the next display")
+            .clicked()
+        {
+            action = Some(DisplayMove::Swap);
+        }
+    });
+
+    action
+}
+
+pub fn render_status(ui: &mut egui::Ui, status: &StatusMessage) -> Option<StatusAction>
+{
+    let mut action = None;
+
+    ui.horizontal(|ui| {
+        ui.add_space(5.0);
+
+        let color = if status.is_error {
+            Color32::from_rgb(230, 100, 100)
+        } else {
+            Color32::from_rgb(120, 200, 120)
+        };
+        ui.add(
+            egui::Label::new(
+                RichText::new(&status.text).font(FontId::proportional(11.0)).color(color),
+            )
+            .wrap(),
+        );
+
+        if status.is_error && ui.small_button("x").on_hover_text("Dismiss").clicked() {
+            action = Some(StatusAction::Dismiss);
+        }
+    });
+
+    if status.offer_relaunch {
+        ui.horizontal(|ui| {
+            ui.add_space(5.0);
+            if ui
+                .button("Restart as administrator")
+                .on_hover_text("The selected window and your settings are kept")
+                .clicked()
+            {
+                action = Some(StatusAction::Relaunch);
+            }
+        });
+    }
+
+    action
+}
+
+pub fn render_preview_button(ui: &mut egui::Ui, enabled: bool, previewing: bool) -> bool
+{
+    ui.add_space(5.0);
+
+    let mut clicked = false;
+
+    ui.with_layout(Layout::top_down(Align::Center), |ui| {
+        let text = if previewing { "Hide Preview" } else { "Preview Placement" };
+        let button = egui::Button::new(
+            RichText::new(text).font(FontId::proportional(12.0)).color(Color32::from_gray(180)),
+        )
+        .min_size(egui::vec2(180.0, 22.0));
+
+        if ui
+            .add_enabled(enabled, button)
+            .on_hover_text("Show where the selected window will end up")
+            .clicked()
+        {
+            clicked = true;
+        }
+    });
+
+    clicked
+}
+
+pub fn render_action_button(
+    ui: &mut egui::Ui,
+    windows: &[WindowInfo],
+    selected_window: Option<usize>,
+) -> Option<usize>
+{
+    ui.add_space(15.0);
+
+    let mut clicked_window = None;
+
+    let button_enabled = selected_window.is_some();
+    let button_text = if let Some(index) = selected_window {
+        if let Some(window) = windows.get(index) {
+            if window.is_borderless { "Restore Borders" } else { "Make Borderless" }
+        } else {
+            "Make Borderless"
+        }
+    } else {
+        "Make Borderless"
+    };
+
+    ui.with_layout(Layout::top_down(Align::Center), |ui| {
+        ui.add_enabled_ui(button_enabled, |ui| {
+            let button = egui::Button::new(
+                RichText::new(button_text).font(FontId::proportional(14.0)).color(
+                    if button_enabled { Color32::from_gray(255) } else { Color32::from_gray(120) },
+                ),
+            )
+            .min_size(egui::vec2(180.0, 35.0));
+
+            if ui.add(button).clicked() && button_enabled {
+                clicked_window = selected_window;
+            }
+        });
+    });
+
+    clicked_window
+}
+
+pub fn render_modifier_controls(ui: &mut egui::Ui, modifiers: &mut WindowModifiers) -> bool
+{
+    let mut changed = false;
+
+    ui.add_space(5.0);
+
+    ui.horizontal(|ui| {
+        ui.add_space(5.0);
+
+        changed |= ui.add(egui::Checkbox::new(&mut modifiers.always_on_top, "")).changed();
+        ui.label(
+            RichText::new("On top").font(FontId::proportional(12.0)).color(Color32::from_gray(180)),
+        );
+
+        ui.add_space(10.0);
+
+        changed |= ui.add(egui::Checkbox::new(&mut modifiers.click_through, "")).changed();
+        ui.label(
+            RichText::new("Click-through")
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_gray(180)),
+        )
+        .on_hover_text("Mouse input passes through the window; undo it here or with Restore All");
+    });
+
+    ui.horizontal(|ui| {
+        ui.add_space(5.0);
+        ui.label(
+            RichText::new("Opacity")
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_gray(180)),
+        );
+
+        let mut percent = (modifiers.opacity as u32 * 100 + 127) / 255;
+        if ui.add(egui::Slider::new(&mut percent, 10..=100).suffix("%")).changed() {
+            modifiers.opacity = (percent * 255 / 100) as u8;
+            changed = true;
+        }
+    });
+
+    changed
+}
+
+pub fn render_hotkey_settings(
+    ui: &mut egui::Ui,
+    bindings: &BTreeMap<HotkeyAction, String>,
+    statuses: &BTreeMap<HotkeyAction, HotkeyStatus>,
+    rebinding: Option<HotkeyAction>,
+) -> Option<HotkeyEdit>
+{
+    let mut edit = None;
+
+    ui.add_space(10.0);
+
+    egui::CollapsingHeader::new(
+        RichText::new("Hotkeys").font(FontId::proportional(12.0)).color(Color32::from_gray(180)),
+    )
+    .id_salt("hotkeys")
+    .show(ui, |ui| {
+        for action in HotkeyAction::ALL {
+            let binding = bindings.get(&action).map(String::as_str).unwrap_or("");
+
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new(action.label())
+                        .font(FontId::proportional(12.0))
+                        .color(Color32::from_gray(180)),
+                );
+
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if ui.add_enabled(!binding.is_empty(), egui::Button::new("x")).clicked() {
+                        edit = Some(HotkeyEdit::Clear(action));
+                    }
+
+                    let text = if rebinding == Some(action) {
+                        "Press a key..."
+                    } else if binding.is_empty() {
+                        "Unbound"
+                    } else {
+                        binding
+                    };
+
+                    let button =
+                        egui::Button::new(RichText::new(text).font(FontId::proportional(12.0)))
+                            .min_size(egui::vec2(110.0, 0.0));
+
+                    if ui.add(button).clicked() {
+                        edit = Some(HotkeyEdit::Rebind(action));
+                    }
+                });
+            });
+
+            if let Some(HotkeyStatus::Invalid(message) | HotkeyStatus::Conflict(message)) =
+                statuses.get(&action)
+            {
+                ui.label(
+                    RichText::new(message)
+                        .font(FontId::proportional(11.0))
+                        .color(Color32::from_rgb(220, 100, 100)),
+                );
+            }
+        }
+    });
+
+    edit
+}
+
+pub fn render_history(
+    ui: &mut egui::Ui,
+    undo: &[HistoryEntry],
+    redo: &[HistoryEntry],
+) -> Option<HistoryAction>
+{
+    let mut action = None;
+
+    ui.add_space(5.0);
+
+    egui::CollapsingHeader::new(
+        RichText::new("History").font(FontId::proportional(12.0)).color(Color32::from_gray(180)),
+    )
+    .id_salt("history")
+    .show(ui, |ui| {
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!undo.is_empty(), egui::Button::new("Undo"))
+                .on_hover_text("Ctrl+Z")
+                .clicked()
+            {
+                action = Some(HistoryAction::Undo(1));
+            }
+            if ui
+                .add_enabled(!redo.is_empty(), egui::Button::new("Redo"))
+                .on_hover_text("Ctrl+Y")
+                .clicked()
+            {
+                action = Some(HistoryAction::Redo(1));
+            }
+        });
+
+        if undo.is_empty() && redo.is_empty() {
+            ui.label(
+                RichText::new("Nothing to undo")
+                    .font(FontId::proportional(11.0))
+                    .color(Color32::from_gray(140)),
+            );
+        }
+
+        for (index, entry) in redo.iter().enumerate() {
+            if history_row(ui, entry, Color32::from_gray(110))
+                .on_hover_text("Click to redo up to here")
+                .clicked()
+            {
+                action = Some(HistoryAction::Redo(redo.len() - index));
+            }
+        }
+
+        for (index, entry) in undo.iter().rev().enumerate() {
+            if history_row(ui, entry, Color32::from_gray(180))
+                .on_hover_text("Click to undo back to here")
+                .clicked()
+            {
+                action = Some(HistoryAction::Undo(index + 1));
+            }
+        }
+    });
+
+    action
+}
+
+fn history_row(ui: &mut egui::Ui, entry: &HistoryEntry, color: Color32) -> egui::Response
+{
+    let titles: Vec<&str> = entry.changes.iter().map(|change| change.title.as_str()).collect();
+    let text = format!("{} - {}", entry.label, titles.join(", "));
+
+    ui.add(
+        egui::Label::new(RichText::new(text).font(FontId::proportional(11.0)).color(color))
+            .truncate()
+            .sense(Sense::click()),
+    )
+}
+
+pub fn render_profile_settings(
+    ui: &mut egui::Ui,
+    profiles: &[Profile],
+    transfer: &mut ProfileTransfer,
+    conflict: ConflictPolicy,
+) -> Option<ProfileAction>
+{
+    let mut action = None;
+
+    ui.add_space(5.0);
+
+    egui::CollapsingHeader::new(
+        RichText::new("Profiles").font(FontId::proportional(12.0)).color(Color32::from_gray(180)),
+    )
+    .id_salt("profiles")
+    .show(ui, |ui| {
+        if profiles.is_empty() {
+            ui.label(
+                RichText::new("No profiles yet")
+                    .font(FontId::proportional(11.0))
+                    .color(Color32::from_gray(140)),
+            );
+        }
+
+        for profile in profiles {
+            let mut checked = transfer.selected.contains(&profile.name);
+            if ui.checkbox(&mut checked, &profile.name).changed() {
+                if checked {
+                    transfer.selected.insert(profile.name.clone());
+                } else {
+                    transfer.selected.remove(&profile.name);
+                }
+            }
+        }
+
+        ui.add(
+            egui::TextEdit::singleline(&mut transfer.path)
+                .hint_text("profiles.json or profiles.toml")
+                .desired_width(ui.available_width() - 10.0),
+        );
+
+        ui.horizontal(|ui| {
+            let has_path = !transfer.path.trim().is_empty();
+            let export_text =
+                if transfer.selected.is_empty() { "Export all" } else { "Export selected" };
+
+            if ui
+                .add_enabled(has_path && !profiles.is_empty(), egui::Button::new(export_text))
+                .clicked()
+            {
+                action = Some(ProfileAction::Export);
+            }
+
+            if ui.add_enabled(has_path, egui::Button::new("Import")).clicked() {
+                action = Some(ProfileAction::Import);
+            }
+
+            let mut selected = conflict;
+            egui::ComboBox::from_id_salt("import_conflict")
+                .selected_text(selected.label())
+                .show_ui(ui, |ui| {
+                    for option in ConflictPolicy::ALL {
+                        if ui.selectable_value(&mut selected, option, option.label()).clicked() {
+                            action = Some(ProfileAction::SetConflict(option));
+                        }
+                    }
+                })
+                .response
+                .on_hover_text(
+                    "What to do when an imported profile has the same name as an existing one",
+                );
+        });
+
+        if ui
+            .button("Import from Borderless Gaming")
+            .on_hover_text("Turns Borderless Gaming favorites into profiles")
+            .clicked()
+        {
+            action = Some(ProfileAction::ImportBorderlessGaming);
+        }
+
+        ui.label(
+            RichText::new("Drop .json or .toml profile files here to import them")
+                .font(FontId::proportional(11.0))
+                .color(Color32::from_gray(140)),
+        );
+
+        match &transfer.status {
+            Some(TransferStatus::Imported(report)) => {
+                ui.label(
+                    RichText::new(format!(
+                        "Imported {} profile(s) from {}",
+                        report.imported.len(),
+                        report.path
+                    ))
+                    .font(FontId::proportional(11.0))
+                    .color(Color32::from_gray(200)),
+                );
+
+                for warning in &report.warnings {
+                    ui.label(
+                        RichText::new(warning)
+                            .font(FontId::proportional(11.0))
+                            .color(Color32::from_rgb(230, 180, 80)),
+                    );
+                }
+            },
+            Some(TransferStatus::Exported { path, count }) => {
+                ui.label(
+                    RichText::new(format!("Exported {} profile(s) to {}", count, path))
+                        .font(FontId::proportional(11.0))
+                        .color(Color32::from_gray(200)),
+                );
+            },
+            Some(TransferStatus::Failed(error)) => {
+                ui.label(
+                    RichText::new(error)
+                        .font(FontId::proportional(11.0))
+                        .color(Color32::from_rgb(230, 100, 100)),
+                );
+            },
+            None => {},
+        }
+    });
+
+    action
+}
+
+pub fn render_filter_settings(
+    ui: &mut egui::Ui,
+    filters: &mut WindowFilters,
+    new_exclude: &mut String,
+) -> bool
+{
+    let mut changed = false;
+
+    ui.add_space(5.0);
+
+    egui::CollapsingHeader::new(
+        RichText::new("Filters").font(FontId::proportional(12.0)).color(Color32::from_gray(180)),
+    )
+    .id_salt("filters")
+    .show(ui, |ui| {
+        let toggles = [
+            (&mut filters.show_tool_windows, "Show tool windows"),
+            (&mut filters.show_owned_windows, "Show owned windows and dialogs"),
+            (&mut filters.show_cloaked_windows, "Show cloaked windows"),
+            (&mut filters.show_untitled_windows, "Show untitled windows"),
+            (&mut filters.show_minimized_windows, "Show minimized windows"),
+        ];
+
+        for (value, label) in toggles {
+            ui.horizontal(|ui| {
+                changed |= ui.add(egui::Checkbox::new(value, "")).changed();
+                ui.label(
+                    RichText::new(label)
+                        .font(FontId::proportional(12.0))
+                        .color(Color32::from_gray(180)),
+                );
+            });
+        }
+
+        ui.add_space(5.0);
+
+        ui.label(
+            RichText::new("Exclude (title prefix or process name):")
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_gray(180)),
+        );
+
+        let mut removed = None;
+        for (index, entry) in filters.exclude.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.small_button("x").clicked() {
+                    removed = Some(index);
+                }
+                ui.label(
+                    RichText::new(entry)
+                        .font(FontId::proportional(12.0))
+                        .color(Color32::from_gray(200)),
+                );
+            });
+        }
+
+        if let Some(index) = removed {
+            filters.exclude.remove(index);
+            changed = true;
+        }
+
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(new_exclude)
+                    .hint_text("Add exclusion...")
+                    .desired_width(ui.available_width() - 50.0),
+            );
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+            if (ui.button("Add").clicked() || submitted) && !new_exclude.trim().is_empty() {
+                filters.exclude.push(new_exclude.trim().to_string());
+                new_exclude.clear();
+                changed = true;
+            }
+        });
+    });
+
+    changed
+}
+
+pub fn render_favorite_settings(
+    ui: &mut egui::Ui,
+    favorites: &mut Vec<Favorite>,
+    displays: &[DisplayInfo],
+) -> bool
+{
+    let mut changed = false;
+
+    if favorites.is_empty() {
+        return false;
+    }
+
+    ui.add_space(5.0);
+
+    egui::CollapsingHeader::new(
+        RichText::new("Favorites").font(FontId::proportional(12.0)).color(Color32::from_gray(180)),
+    )
+    .id_salt("favorites")
+    .show(ui, |ui| {
+        let mut removed = None;
+
+        for (index, favorite) in favorites.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.small_button("x").clicked() {
+                    removed = Some(index);
+                }
+                ui.label(
+                    RichText::new(&favorite.process_name)
+                        .font(FontId::proportional(12.0))
+                        .color(Color32::from_gray(200)),
+                );
+            });
+
+            ui.horizontal(|ui| {
+                let mut pattern = favorite.title_contains.clone().unwrap_or_default();
+                if ui
+                    .add(
+                        egui::TextEdit::singleline(&mut pattern)
+                            .hint_text("Title contains...")
+                            .desired_width(140.0),
+                    )
+                    .changed()
+                {
+                    favorite.title_contains = (!pattern.is_empty()).then_some(pattern);
+                    changed = true;
+                }
+
+                let placement_text = match (&favorite.display, favorite.resize_to_screen) {
+                    (_, false) => "Keep position".to_string(),
+                    (Some(display), true) => display.clone(),
+                    (None, true) => "Primary display".to_string(),
+                };
+
+                egui::ComboBox::from_id_salt(("favorite_placement", index))
+                    .selected_text(placement_text)
+                    .show_ui(ui, |ui| {
+                        if ui
+                            .selectable_label(!favorite.resize_to_screen, "Keep position")
+                            .clicked()
+                        {
+                            favorite.resize_to_screen = false;
+                            changed = true;
+                        }
+
+                        for display in displays {
+                            let selected = favorite.resize_to_screen
+                                && favorite.display.as_deref() == Some(display.name.as_str());
+                            if ui.selectable_label(selected, display.display_text()).clicked() {
+                                favorite.resize_to_screen = true;
+                                favorite.display = Some(display.name.clone());
+                                changed = true;
+                            }
+                        }
+                    });
+
+                egui::ComboBox::from_id_salt(("favorite_frame_mode", index))
+                    .selected_text(favorite.frame_mode.label())
+                    .show_ui(ui, |ui| {
+                        for option in FrameMode::ALL {
+                            changed |= ui
+                                .selectable_value(&mut favorite.frame_mode, option, option.label())
+                                .changed();
+                        }
+                    });
+            });
+
+            ui.add_space(3.0);
+        }
+
+        if let Some(index) = removed {
+            favorites.remove(index);
+            changed = true;
+        }
+    });
+
+    changed
+}
+
+pub fn render_log_panel(
+    ui: &mut egui::Ui,
+    level: &mut LogLevel,
+    redact_titles: &mut bool,
+    entries: &[LogEntry],
+    path: Option<&Path>,
+) -> bool
+{
+    let mut changed = false;
+
+    ui.add_space(5.0);
+
+    egui::CollapsingHeader::new(
+        RichText::new("Log").font(FontId::proportional(12.0)).color(Color32::from_gray(180)),
+    )
+    .id_salt("log")
+    .show(ui, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new("Level")
+                    .font(FontId::proportional(12.0))
+                    .color(Color32::from_gray(180)),
+            );
+
+            egui::ComboBox::from_id_salt("log_level").selected_text(level.label()).show_ui(
+                ui,
+                |ui| {
+                    for option in LogLevel::ALL {
+                        changed |= ui.selectable_value(level, option, option.label()).changed();
+                    }
+                },
+            );
+        });
+
+        ui.horizontal(|ui| {
+            changed |= ui.add(egui::Checkbox::new(redact_titles, "")).changed();
+            ui.label(
+                RichText::new("Redact window titles")
+                    .font(FontId::proportional(12.0))
+                    .color(Color32::from_gray(180)),
+            )
+            .on_hover_text("Writes <redacted> instead of window titles to the log");
+        });
+
+        if let Some(path) = path {
+            ui.label(
+                RichText::new(path.display().to_string())
+                    .font(FontId::proportional(10.0))
+                    .color(Color32::from_gray(140)),
+            );
+        }
+
+        ui.add_space(5.0);
+
+        egui::ScrollArea::vertical()
+            .id_salt("log_entries")
+            .max_height(200.0)
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                if entries.is_empty() {
+                    ui.label(
+                        RichText::new("No log entries yet")
+                            .font(FontId::proportional(11.0))
+                            .color(Color32::from_gray(140)),
+                    );
+                }
+
+                for entry in entries {
+                    let color = match entry.level {
+                        LogLevel::Error => Color32::from_rgb(230, 100, 100),
+                        LogLevel::Warn => Color32::from_rgb(230, 180, 80),
+                        LogLevel::Info => Color32::from_gray(200),
+                        LogLevel::Debug => Color32::from_gray(140),
+                    };
+                    ui.add(
+                        egui::Label::new(
+                            RichText::new(entry.line()).font(FontId::monospace(10.0)).color(color),
+                        )
+                        .wrap(),
+                    );
+                }
+            });
+    });
+
+    changed
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn window(title: &str, process_name: &str) -> WindowInfo
+    {
+        WindowInfo {
+            title: title.to_string(),
+            process_name: process_name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn groups_follow_the_sort_order()
+    {
+        let windows = vec![
+            window("zsh", "WindowsTerminal"),
+            window("Settings", "SystemSettings"),
+            window("cmd", "windowsterminal"),
+            window("Brave", "brave"),
+        ];
+        let order = WindowSort::Title.sorted_indices(&windows);
+
+        assert_eq!(
+            group_by_process_name(&windows, &order),
+            [("brave", vec![3]), ("windowsterminal", vec![2, 0]), ("SystemSettings", vec![1])]
+        );
+    }
 }
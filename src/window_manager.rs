@@ -1,230 +1,1980 @@
-use std::sync::{Arc, Mutex};
+use crate::{
+    backdrop,
+    config::{Profile, WindowFilters},
+    cursor,
+    history::{self, History, HistoryChange, HistoryEntry, WindowState},
+    logging::{self, LogLevel},
+    process::{self, Elevation},
+    taskbar,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        mpsc::{Receiver, Sender},
+    },
+};
 use windows::Win32::{
-    Foundation::{HWND, LPARAM, RECT},
-    Graphics::Gdi::{
-        BI_RGB, BITMAPINFO, BITMAPINFOHEADER, CreateCompatibleBitmap, CreateCompatibleDC,
-        DIB_RGB_COLORS, DeleteDC, DeleteObject, EnumDisplayMonitors, GetDC, GetDIBits,
-        GetMonitorInfoW, HBITMAP, HDC, HGDIOBJ, HMONITOR, MONITORINFO, ReleaseDC, SelectObject,
+    Foundation::{COLORREF, HWND, LPARAM, POINT, RECT},
+    Graphics::{
+        Dwm::{DWMWA_CLOAKED, DWMWA_EXTENDED_FRAME_BOUNDS, DwmGetWindowAttribute},
+        Gdi::{
+            BI_RGB, BITMAPINFO, BITMAPINFOHEADER, ClientToScreen, CreateCompatibleBitmap,
+            CreateCompatibleDC, DIB_RGB_COLORS, DeleteDC, DeleteObject, EnumDisplayMonitors, GetDC,
+            GetDIBits, GetMonitorInfoW, HBITMAP, HDC, HGDIOBJ, HMONITOR, MONITOR_DEFAULTTONEAREST,
+            MONITORINFO, MONITORINFOEXW, MonitorFromWindow, ReleaseDC, SelectObject,
+        },
     },
-    System::Diagnostics::ToolHelp::{
-        CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW,
-        TH32CS_SNAPPROCESS,
+    System::{
+        Diagnostics::ToolHelp::{
+            CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW,
+            TH32CS_SNAPPROCESS,
+        },
+        Threading::GetCurrentProcessId,
     },
     UI::WindowsAndMessaging::{
-        DrawIconEx, EnumWindows, GCLP_HICON, GWL_STYLE, GetClassLongPtrW, GetSystemMetrics,
-        GetWindowLongW, GetWindowTextW, GetWindowThreadProcessId, HWND_TOP, ICON_SMALL,
-        IsWindowVisible, SM_CXSCREEN, SM_CYSCREEN, SWP_FRAMECHANGED, SWP_NOMOVE, SWP_NOSIZE,
-        SWP_NOZORDER, SendMessageW, SetWindowLongW, SetWindowPos, WM_GETICON, WS_BORDER,
-        WS_CAPTION, WS_DLGFRAME, WS_THICKFRAME,
+        AdjustWindowRectEx, DrawIconEx, EnumWindows, GCLP_HICON, GW_OWNER, GWL_EXSTYLE, GWL_STYLE,
+        GetClassLongPtrW, GetClassNameW, GetClientRect, GetForegroundWindow,
+        GetLayeredWindowAttributes, GetMenu, GetSystemMetrics, GetWindow, GetWindowLongW,
+        GetWindowRect, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId,
+        HWND_NOTOPMOST, HWND_TOP, HWND_TOPMOST, ICON_SMALL, IsIconic, IsWindow, IsWindowVisible,
+        IsZoomed, LAYERED_WINDOW_ATTRIBUTES_FLAGS, LWA_ALPHA, SM_CXSCREEN, SM_CYSCREEN,
+        SWP_FRAMECHANGED, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER, SendMessageW,
+        SetLayeredWindowAttributes, SetWindowLongW, SetWindowPos, WINDOW_EX_STYLE, WINDOW_STYLE,
+        WM_GETICON, WS_BORDER, WS_CAPTION, WS_CHILD, WS_CLIPCHILDREN, WS_CLIPSIBLINGS, WS_DISABLED,
+        WS_DLGFRAME, WS_EX_ACCEPTFILES, WS_EX_APPWINDOW, WS_EX_CLIENTEDGE, WS_EX_COMPOSITED,
+        WS_EX_CONTEXTHELP, WS_EX_CONTROLPARENT, WS_EX_DLGMODALFRAME, WS_EX_LAYERED,
+        WS_EX_LAYOUTRTL, WS_EX_LEFTSCROLLBAR, WS_EX_MDICHILD, WS_EX_NOACTIVATE,
+        WS_EX_NOINHERITLAYOUT, WS_EX_NOPARENTNOTIFY, WS_EX_NOREDIRECTIONBITMAP, WS_EX_RIGHT,
+        WS_EX_RTLREADING, WS_EX_STATICEDGE, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_EX_TRANSPARENT,
+        WS_EX_WINDOWEDGE, WS_HSCROLL, WS_MAXIMIZE, WS_MAXIMIZEBOX, WS_MINIMIZE, WS_MINIMIZEBOX,
+        WS_POPUP, WS_SYSMENU, WS_THICKFRAME, WS_VISIBLE, WS_VSCROLL,
     },
 };
 
-#[derive(Debug, Clone)]
-pub struct WindowInfo
-{
-    pub hwnd: isize,
-    pub title: String,
-    pub process_name: String,
-    pub is_borderless: bool,
-    pub icon_data: Option<Vec<u8>>,
+const BORDER_STYLES: u32 = WS_BORDER.0 | WS_CAPTION.0 | WS_THICKFRAME.0 | WS_DLGFRAME.0;
+
+// Games often snap or clamp their own size, so each edge may miss the placement by a few pixels.
+const PLACEMENT_TOLERANCE: i32 = 8;
+
+static KNOWN_DISPLAYS: Mutex<Vec<DisplayInfo>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WindowInfo
+{
+    pub hwnd: isize,
+    pub title: String,
+    pub process_name: String,
+    pub is_borderless: bool,
+    pub modifiers: WindowModifiers,
+    pub process_id: u32,
+    pub thread_id: u32,
+    pub class_name: String,
+    pub exe_path: Option<String>,
+    pub product_name: Option<String>,
+    pub product_version: Option<String>,
+    pub elevation: Elevation,
+    pub rect: WindowRect,
+    pub client_rect: WindowRect,
+    pub monitor: Option<String>,
+    pub style: u32,
+    pub ex_style: u32,
+    pub is_owned: bool,
+    pub is_cloaked: bool,
+    pub is_minimized: bool,
+    pub is_maximized: bool,
+    pub z_order: usize,
+    #[serde(skip)]
+    pub icon_data: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct WindowRect
+{
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameMode
+{
+    #[default]
+    Full,
+    CaptionOnly,
+    FrameOnly,
+    ThinBorder,
+}
+
+impl FrameMode
+{
+    pub const ALL: [Self; 4] = [Self::Full, Self::CaptionOnly, Self::FrameOnly, Self::ThinBorder];
+
+    pub fn label(self) -> &'static str
+    {
+        match self {
+            Self::Full => "Full borderless",
+            Self::CaptionOnly => "Remove caption",
+            Self::FrameOnly => "Remove frame",
+            Self::ThinBorder => "Thin border",
+        }
+    }
+
+    pub fn apply(self, style: u32) -> u32
+    {
+        match self {
+            Self::Full => full_borderless_style(style),
+            Self::CaptionOnly => caption_removed_style(style),
+            Self::FrameOnly => frame_removed_style(style),
+            Self::ThinBorder => thin_border_style(style),
+        }
+    }
+}
+
+pub const SIZE_PRESETS: [(i32, i32, &str); 11] = [
+    (1280, 720, "16:9"),
+    (1600, 900, "16:9"),
+    (1920, 1080, "16:9"),
+    (2560, 1440, "16:9"),
+    (3840, 2160, "16:9"),
+    (2560, 1080, "21:9"),
+    (3440, 1440, "21:9"),
+    (1024, 768, "4:3"),
+    (1280, 960, "4:3"),
+    (1600, 1200, "4:3"),
+    (1920, 1440, "4:3"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Anchor
+{
+    #[default]
+    Center,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Anchor
+{
+    pub const ALL: [Self; 5] =
+        [Self::Center, Self::TopLeft, Self::TopRight, Self::BottomLeft, Self::BottomRight];
+
+    pub fn label(self) -> &'static str
+    {
+        match self {
+            Self::Center => "Centre",
+            Self::TopLeft => "Top left",
+            Self::TopRight => "Top right",
+            Self::BottomLeft => "Bottom left",
+            Self::BottomRight => "Bottom right",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowSize
+{
+    pub width: i32,
+    pub height: i32,
+    #[serde(default)]
+    pub anchor: Anchor,
+}
+
+impl WindowSize
+{
+    pub fn parse(text: &str) -> Option<(i32, i32)>
+    {
+        let (width, height) = text.trim().split_once(['x', 'X', '*'])?;
+        let width = width.trim().parse::<i32>().ok()?;
+        let height = height.trim().parse::<i32>().ok()?;
+
+        (width > 0 && height > 0).then_some((width, height))
+    }
+
+    pub fn label(&self) -> String
+    {
+        format!("{}x{}", self.width, self.height)
+    }
+
+    fn place(&self, screen: (i32, i32, i32, i32), outer: (i32, i32)) -> (i32, i32, i32, i32)
+    {
+        let (screen_x, screen_y, screen_width, screen_height) = screen;
+        let (width, height) = outer;
+
+        let x = match self.anchor {
+            Anchor::Center => screen_x + (screen_width - width) / 2,
+            Anchor::TopLeft | Anchor::BottomLeft => screen_x,
+            Anchor::TopRight | Anchor::BottomRight => screen_x + screen_width - width,
+        };
+        let y = match self.anchor {
+            Anchor::Center => screen_y + (screen_height - height) / 2,
+            Anchor::TopLeft | Anchor::TopRight => screen_y,
+            Anchor::BottomLeft | Anchor::BottomRight => screen_y + screen_height - height,
+        };
+
+        (x, y, width, height)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowModifiers
+{
+    pub always_on_top: bool,
+    pub opacity: u8,
+    pub click_through: bool,
+}
+
+impl Default for WindowModifiers
+{
+    fn default() -> Self
+    {
+        Self { always_on_top: false, opacity: 255, click_through: false }
+    }
+}
+
+impl WindowModifiers
+{
+    pub fn is_default(&self) -> bool
+    {
+        *self == Self::default()
+    }
+
+    pub fn badges(&self) -> Vec<String>
+    {
+        let mut badges = Vec::new();
+
+        if self.always_on_top {
+            badges.push("[T]".to_string());
+        }
+        if self.opacity < 255 {
+            badges.push(format!("[{}%]", self.opacity as u32 * 100 / 255));
+        }
+        if self.click_through {
+            badges.push("[C]".to_string());
+        }
+
+        badges
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BorderlessEffects
+{
+    pub hide_taskbar: bool,
+    pub lock_cursor: bool,
+    pub backdrop: Option<[u8; 3]>,
+}
+
+impl BorderlessEffects
+{
+    fn start(&self, hwnd: isize)
+    {
+        if self.hide_taskbar {
+            taskbar::watch(hwnd);
+        }
+        if self.lock_cursor {
+            cursor::lock(hwnd);
+        }
+        if let Some(color) = self.backdrop {
+            backdrop::show(hwnd, color);
+        }
+    }
+
+    fn stop(hwnd: isize)
+    {
+        taskbar::unwatch(hwnd);
+        cursor::unlock(hwnd);
+        backdrop::hide(hwnd);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DisplayInfo
+{
+    pub name: String,
+    pub device_name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub is_primary: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowSort
+{
+    #[default]
+    Title,
+    Process,
+    State,
+    Monitor,
+    RecentlyActive,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WindowEvent
+{
+    Opened
+    {
+        hwnd: isize, title: String, process_name: String
+    },
+    Closed
+    {
+        hwnd: isize
+    },
+    BorderChanged
+    {
+        hwnd: isize, is_borderless: bool
+    },
+}
+
+impl WindowInfo
+{
+    pub fn display_text(&self) -> String
+    {
+        let max_title_len = 30;
+        let max_process_len = 15;
+
+        let truncated_title = if self.title.trim().is_empty() {
+            "(untitled)".to_string()
+        } else if self.title.chars().count() > max_title_len {
+            let truncated: String = self.title.chars().take(max_title_len - 3).collect();
+            format!("{}...", truncated)
+        } else {
+            self.title.clone()
+        };
+
+        let truncated_process = if self.process_name.chars().count() > max_process_len {
+            let truncated: String = self.process_name.chars().take(max_process_len - 3).collect();
+            format!("{}...", truncated)
+        } else {
+            self.process_name.clone()
+        };
+
+        format!("{} ({})", truncated_title, truncated_process)
+    }
+
+    pub fn tooltip_text(&self) -> String
+    {
+        let mut lines = vec![self.title.clone()];
+
+        if let Some(exe_path) = &self.exe_path {
+            lines.push(exe_path.clone());
+        }
+
+        match (&self.product_name, &self.product_version) {
+            (Some(name), Some(version)) => lines.push(format!("{} {}", name, version)),
+            (Some(name), None) => lines.push(name.clone()),
+            _ => {},
+        }
+
+        lines.push(format!("PID {} - {}", self.process_id, self.class_name));
+
+        lines.join("\n")
+    }
+
+    pub fn requires_elevation(&self) -> bool
+    {
+        self.elevation == Elevation::Elevated && !process::is_elevated()
+    }
+}
+
+impl WindowRect
+{
+    fn from_rect(rect: RECT) -> Self
+    {
+        Self {
+            x: rect.left,
+            y: rect.top,
+            width: rect.right - rect.left,
+            height: rect.bottom - rect.top,
+        }
+    }
+}
+
+impl std::fmt::Display for WindowRect
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        write!(f, "{}x{} at ({}, {})", self.width, self.height, self.x, self.y)
+    }
+}
+
+impl WindowSort
+{
+    pub const ALL: [Self; 5] =
+        [Self::Title, Self::Process, Self::State, Self::Monitor, Self::RecentlyActive];
+
+    pub fn label(self) -> &'static str
+    {
+        match self {
+            Self::Title => "Title",
+            Self::Process => "Process",
+            Self::State => "Borderless first",
+            Self::Monitor => "Display",
+            Self::RecentlyActive => "Recently active",
+        }
+    }
+
+    pub fn sorted_indices(self, windows: &[WindowInfo]) -> Vec<usize>
+    {
+        let mut order: Vec<usize> = (0..windows.len()).collect();
+        let by_title =
+            |a: &WindowInfo, b: &WindowInfo| a.title.to_lowercase().cmp(&b.title.to_lowercase());
+
+        order.sort_by(|&a, &b| {
+            let (a, b) = (&windows[a], &windows[b]);
+            match self {
+                Self::Title => by_title(a, b),
+                Self::Process => a
+                    .process_name
+                    .to_lowercase()
+                    .cmp(&b.process_name.to_lowercase())
+                    .then_with(|| by_title(a, b)),
+                Self::State => b.is_borderless.cmp(&a.is_borderless).then_with(|| by_title(a, b)),
+                Self::Monitor => match (&a.monitor, &b.monitor) {
+                    (Some(a_monitor), Some(b_monitor)) => natural_cmp(a_monitor, b_monitor),
+                    (a_monitor, b_monitor) => a_monitor.is_some().cmp(&b_monitor.is_some()),
+                }
+                .then_with(|| by_title(a, b)),
+                Self::RecentlyActive => a.z_order.cmp(&b.z_order),
+            }
+        });
+
+        order
+    }
+}
+
+impl DisplayInfo
+{
+    pub fn display_text(&self) -> String
+    {
+        let primary_indicator = if self.is_primary { " (Primary)" } else { "" };
+        format!("{} - {}x{}{}", self.name, self.width, self.height, primary_indicator)
+    }
+}
+
+#[derive(Clone)]
+pub struct WindowManager
+{
+    windows: Arc<Mutex<Vec<WindowInfo>>>,
+    refresh_in_progress: Arc<Mutex<bool>>,
+    subscribers: Arc<Mutex<Vec<Sender<WindowEvent>>>>,
+    managed_windows: Arc<Mutex<HashMap<isize, ManagedWindow>>>,
+    filters: Arc<Mutex<WindowFilters>>,
+    window_size: Arc<Mutex<Option<WindowSize>>>,
+    history: Arc<Mutex<History>>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct ManagedWindow
+{
+    frame: Option<FrameState>,
+    original: Option<OriginalState>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FrameState
+{
+    mode: FrameMode,
+    style: u32,
+    fill: bool,
+    size: Option<WindowSize>,
+    effects: BorderlessEffects,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct OriginalState
+{
+    ex_style: u32,
+    layered: Option<(COLORREF, u8, LAYERED_WINDOW_ATTRIBUTES_FLAGS)>,
+}
+
+impl OriginalState
+{
+    fn capture(hwnd: HWND) -> Self
+    {
+        unsafe {
+            let ex_style = GetWindowLongW(hwnd, GWL_EXSTYLE) as u32;
+            let layered = if (ex_style & WS_EX_LAYERED.0) != 0 {
+                let mut key = COLORREF(0);
+                let mut alpha = 255u8;
+                let mut flags = LAYERED_WINDOW_ATTRIBUTES_FLAGS(0);
+                GetLayeredWindowAttributes(hwnd, Some(&mut key), Some(&mut alpha), Some(&mut flags))
+                    .ok()
+                    .map(|()| (key, alpha, flags))
+            } else {
+                None
+            };
+
+            Self { ex_style, layered }
+        }
+    }
+}
+
+struct Tracked<'a>
+{
+    manager: &'a WindowManager,
+    label: String,
+}
+
+impl Drop for Tracked<'_>
+{
+    fn drop(&mut self)
+    {
+        if let Some(before) = history::end() {
+            self.manager.record(&self.label, before);
+        }
+    }
+}
+
+struct EnumContext
+{
+    windows: Vec<WindowInfo>,
+    filters: WindowFilters,
+}
+
+impl WindowManager
+{
+    pub fn new() -> Self
+    {
+        Self {
+            windows: Arc::new(Mutex::new(Vec::new())),
+            refresh_in_progress: Arc::new(Mutex::new(false)),
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+            managed_windows: Arc::new(Mutex::new(HashMap::new())),
+            filters: Arc::new(Mutex::new(WindowFilters::default())),
+            window_size: Arc::new(Mutex::new(None)),
+            history: Arc::new(Mutex::new(History::default())),
+        }
+    }
+
+    pub fn refresh_windows_async(&self) -> std::sync::mpsc::Receiver<Vec<WindowInfo>>
+    {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let refresh_flag = Arc::clone(&self.refresh_in_progress);
+        let filters = self.filters.lock().unwrap().clone();
+
+        std::thread::spawn(move || {
+            {
+                let mut in_progress = refresh_flag.lock().unwrap();
+                if *in_progress {
+                    let _ = sender.send(Vec::new());
+                    return;
+                }
+                *in_progress = true;
+            }
+
+            let windows = enumerate_windows(filters);
+
+            *refresh_flag.lock().unwrap() = false;
+
+            let _ = sender.send(windows);
+        });
+
+        receiver
+    }
+
+    pub fn refresh_windows(&self)
+    {
+        let filters = self.filters.lock().unwrap().clone();
+        self.set_windows(enumerate_windows(filters));
+    }
+
+    pub fn set_filters(&self, filters: WindowFilters)
+    {
+        *self.filters.lock().unwrap() = filters;
+    }
+
+    pub fn set_window_size(&self, window_size: Option<WindowSize>)
+    {
+        *self.window_size.lock().unwrap() = window_size;
+    }
+
+    pub fn get_windows(&self) -> Vec<WindowInfo>
+    {
+        self.windows.lock().unwrap().clone()
+    }
+
+    pub fn window_count(&self) -> usize
+    {
+        self.windows.lock().unwrap().len()
+    }
+
+    pub fn set_windows(&self, mut windows: Vec<WindowInfo>)
+    {
+        {
+            let managed = self.managed_windows.lock().unwrap();
+            for window in &mut windows {
+                if managed.get(&window.hwnd).is_some_and(|state| state.frame.is_some()) {
+                    window.is_borderless = true;
+                }
+            }
+        }
+
+        let events = {
+            let mut current = self.windows.lock().unwrap();
+            let mut events = Vec::new();
+
+            for old in current.iter() {
+                if !windows.iter().any(|window| window.hwnd == old.hwnd) {
+                    events.push(WindowEvent::Closed { hwnd: old.hwnd });
+                }
+            }
+
+            for new in &windows {
+                match current.iter().find(|window| window.hwnd == new.hwnd) {
+                    None => events.push(WindowEvent::Opened {
+                        hwnd: new.hwnd,
+                        title: new.title.clone(),
+                        process_name: new.process_name.clone(),
+                    }),
+                    Some(old) if old.is_borderless != new.is_borderless => {
+                        events.push(WindowEvent::BorderChanged {
+                            hwnd: new.hwnd,
+                            is_borderless: new.is_borderless,
+                        })
+                    },
+                    Some(_) => {},
+                }
+            }
+
+            *current = windows;
+            events
+        };
+
+        self.notify(events);
+    }
+
+    pub fn subscribe(&self) -> Receiver<WindowEvent>
+    {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.subscribers.lock().unwrap().push(sender);
+        receiver
+    }
+
+    fn notify(&self, events: Vec<WindowEvent>)
+    {
+        if events.is_empty() {
+            return;
+        }
+
+        for event in events.iter().filter(|_| logging::enabled(LogLevel::Debug)) {
+            match event {
+                WindowEvent::Opened { hwnd, title, process_name } => logging::debug(
+                    "enumeration",
+                    format!(
+                        "Window opened hwnd=0x{:X} title={} process={:?}",
+                        hwnd,
+                        logging::title(title),
+                        process_name
+                    ),
+                ),
+                WindowEvent::Closed { hwnd } => {
+                    logging::debug("enumeration", format!("Window closed hwnd=0x{:X}", hwnd))
+                },
+                WindowEvent::BorderChanged { hwnd, is_borderless } => logging::debug(
+                    "enumeration",
+                    format!("Border state changed hwnd=0x{:X} borderless={}", hwnd, is_borderless),
+                ),
+            }
+        }
+
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|subscriber| events.iter().all(|event| subscriber.send(event.clone()).is_ok()));
+    }
+
+    fn mark_borderless(&self, hwnd: isize, is_borderless: bool)
+    {
+        let changed = {
+            let mut windows = self.windows.lock().unwrap();
+            match windows.iter_mut().find(|window| window.hwnd == hwnd) {
+                Some(window) if window.is_borderless != is_borderless => {
+                    window.is_borderless = is_borderless;
+                    true
+                },
+                _ => false,
+            }
+        };
+
+        if changed {
+            self.notify(vec![WindowEvent::BorderChanged { hwnd, is_borderless }]);
+        }
+    }
+
+    fn mark_modifiers(&self, hwnd: isize, modifiers: WindowModifiers)
+    {
+        let mut windows = self.windows.lock().unwrap();
+        if let Some(window) = windows.iter_mut().find(|window| window.hwnd == hwnd) {
+            window.modifiers = modifiers;
+        }
+    }
+
+    pub fn get_displays(&self) -> Vec<DisplayInfo>
+    {
+        enumerate_displays()
+    }
+
+    pub fn is_borderless(&self, hwnd: isize) -> bool
+    {
+        if self.frame_mode(hwnd).is_some() {
+            return true;
+        }
+
+        let hwnd = HWND(hwnd as *mut std::ffi::c_void);
+        let current_style = unsafe { GetWindowLongW(hwnd, GWL_STYLE) } as u32;
+        (current_style & BORDER_STYLES) == 0
+    }
+
+    pub fn frame_mode(&self, hwnd: isize) -> Option<FrameMode>
+    {
+        self.frame(hwnd).map(|frame| frame.mode)
+    }
+
+    fn frame(&self, hwnd: isize) -> Option<FrameState>
+    {
+        self.managed_windows.lock().unwrap().get(&hwnd).and_then(|state| state.frame)
+    }
+
+    pub fn toggle_borderless(
+        &self,
+        hwnd: isize,
+        resize_to_screen: bool,
+        selected_display: Option<&DisplayInfo>,
+        mode: FrameMode,
+        effects: BorderlessEffects,
+    ) -> anyhow::Result<()>
+    {
+        if self.is_borderless(hwnd) {
+            self.restore_borders(hwnd)
+        } else {
+            self.set_borderless(hwnd, resize_to_screen, selected_display, mode, effects)
+        }
+    }
+
+    pub fn set_borderless(
+        &self,
+        hwnd: isize,
+        resize_to_screen: bool,
+        selected_display: Option<&DisplayInfo>,
+        mode: FrameMode,
+        effects: BorderlessEffects,
+    ) -> anyhow::Result<()>
+    {
+        let size = *self.window_size.lock().unwrap();
+        self.set_borderless_sized(hwnd, resize_to_screen, selected_display, mode, size, effects)
+    }
+
+    pub fn set_borderless_sized(
+        &self,
+        hwnd: isize,
+        resize_to_screen: bool,
+        selected_display: Option<&DisplayInfo>,
+        mode: FrameMode,
+        size: Option<WindowSize>,
+        effects: BorderlessEffects,
+    ) -> anyhow::Result<()>
+    {
+        let result = self
+            .make_borderless(hwnd, resize_to_screen, selected_display, mode, size, effects)
+            .and_then(|placed| match placed {
+                Some(expected) => verify_rect(hwnd, expected),
+                None => Ok(()),
+            })
+            .inspect(|()| effects.start(hwnd));
+        let details = format!(
+            "mode={:?} resize={} display={} size={}",
+            mode,
+            resize_to_screen,
+            selected_display.map_or("primary", |display| display.name.as_str()),
+            size.map_or_else(|| "fill".to_string(), |size| size.label()),
+        );
+        self.log_result("Make borderless", hwnd, &details, &result);
+        result
+    }
+
+    fn make_borderless(
+        &self,
+        hwnd: isize,
+        resize_to_screen: bool,
+        selected_display: Option<&DisplayInfo>,
+        mode: FrameMode,
+        size: Option<WindowSize>,
+        effects: BorderlessEffects,
+    ) -> anyhow::Result<Option<RECT>>
+    {
+        let _tracked = self.track("Make borderless");
+        let handle = HWND(hwnd as *mut std::ffi::c_void);
+
+        let placed = unsafe {
+            if !IsWindow(Some(handle)).as_bool() {
+                anyhow::bail!("Window no longer exists");
+            }
+            self.touch(hwnd);
+
+            let current_style = GetWindowLongW(handle, GWL_STYLE) as u32;
+            let (original_style, previous_frame) = {
+                let mut managed = self.managed_windows.lock().unwrap();
+                let state = managed.entry(hwnd).or_default();
+                let previous_frame = state.frame;
+                let original_style = previous_frame.map_or(current_style, |frame| frame.style);
+                state.frame = Some(FrameState {
+                    mode,
+                    style: original_style,
+                    fill: resize_to_screen && size.is_none(),
+                    size: size.filter(|_| resize_to_screen),
+                    effects,
+                });
+                (original_style, previous_frame)
+            };
+
+            let style = mode.apply(restored_style(original_style, current_style));
+            SetWindowLongW(handle, GWL_STYLE, style as i32);
+
+            if let Err(e) = verify_style(hwnd, style) {
+                if let Some(state) = self.managed_windows.lock().unwrap().get_mut(&hwnd) {
+                    state.frame = previous_frame;
+                }
+                return Err(e);
+            }
+
+            let placed = if resize_to_screen {
+                placement(handle, style, selected_display, size).and_then(
+                    |(x, y, width, height)| {
+                        SetWindowPos(
+                            handle,
+                            Some(HWND_TOP),
+                            x,
+                            y,
+                            width,
+                            height,
+                            SWP_FRAMECHANGED | SWP_NOZORDER,
+                        )?;
+
+                        Ok(Some(RECT { left: x, top: y, right: x + width, bottom: y + height }))
+                    },
+                )
+            } else {
+                refresh_frame(handle).map(|()| None).map_err(anyhow::Error::from)
+            };
+
+            // Put the old frame back when the window could not be placed, so a failed toggle leaves
+            // it as it was instead of marking a half-applied frame as borderless.
+            match placed {
+                Ok(placed) => placed,
+                Err(e) => {
+                    SetWindowLongW(handle, GWL_STYLE, current_style as i32);
+                    let _ = refresh_frame(handle);
+                    if let Some(state) = self.managed_windows.lock().unwrap().get_mut(&hwnd) {
+                        state.frame = previous_frame;
+                    }
+                    return Err(explain_failure(hwnd, e));
+                },
+            }
+        };
+
+        self.mark_borderless(hwnd, true);
+
+        Ok(placed)
+    }
+
+    pub fn restore_borders(&self, hwnd: isize) -> anyhow::Result<()>
+    {
+        let result = self.reinstate_borders(hwnd);
+        self.log_result("Restore borders", hwnd, "", &result);
+        result
+    }
+
+    fn reinstate_borders(&self, hwnd: isize) -> anyhow::Result<()>
+    {
+        let _tracked = self.track("Restore borders");
+        let handle = HWND(hwnd as *mut std::ffi::c_void);
+
+        unsafe {
+            if !IsWindow(Some(handle)).as_bool() {
+                anyhow::bail!("Window no longer exists");
+            }
+            self.touch(hwnd);
+
+            let current_style = GetWindowLongW(handle, GWL_STYLE) as u32;
+            let frame = {
+                let mut managed = self.managed_windows.lock().unwrap();
+                let frame = managed.get_mut(&hwnd).and_then(|state| state.frame.take());
+                if managed.get(&hwnd).is_some_and(|state| state.original.is_none()) {
+                    managed.remove(&hwnd);
+                }
+                frame
+            };
+
+            let style = match frame {
+                Some(frame) => restored_style(frame.style, current_style),
+                None => current_style | WS_CAPTION.0 | WS_THICKFRAME.0,
+            };
+            SetWindowLongW(handle, GWL_STYLE, style as i32);
+
+            if let Err(e) = verify_style(hwnd, style) {
+                if let Some(frame) = frame {
+                    self.managed_windows.lock().unwrap().entry(hwnd).or_default().frame =
+                        Some(frame);
+                }
+                return Err(e);
+            }
+
+            refresh_frame(handle)?;
+        }
+
+        BorderlessEffects::stop(hwnd);
+        self.mark_borderless(hwnd, false);
+
+        Ok(())
+    }
+
+    pub fn modifiers(&self, hwnd: isize) -> WindowModifiers
+    {
+        let handle = HWND(hwnd as *mut std::ffi::c_void);
+        read_modifiers(handle, unsafe { GetWindowLongW(handle, GWL_EXSTYLE) } as u32)
+    }
+
+    pub fn set_modifiers(&self, hwnd: isize, modifiers: WindowModifiers) -> anyhow::Result<()>
+    {
+        let result = self.apply_modifiers(hwnd, modifiers);
+        let details = format!(
+            "on_top={} opacity={} click_through={}",
+            modifiers.always_on_top, modifiers.opacity, modifiers.click_through
+        );
+        self.log_result("Change window modifiers", hwnd, &details, &result);
+        result
+    }
+
+    fn apply_modifiers(&self, hwnd: isize, modifiers: WindowModifiers) -> anyhow::Result<()>
+    {
+        let _tracked = self.track("Change window modifiers");
+        let handle = HWND(hwnd as *mut std::ffi::c_void);
+
+        if !unsafe { IsWindow(Some(handle)) }.as_bool() {
+            anyhow::bail!("Window no longer exists");
+        }
+        self.touch(hwnd);
+
+        let mut managed = self.managed_windows.lock().unwrap();
+        let state = managed.entry(hwnd).or_default();
+        let original = *state.original.get_or_insert_with(|| OriginalState::capture(handle));
+
+        let needs_layer = modifiers.opacity < 255 || modifiers.click_through;
+        let mut ex_style = original.ex_style;
+        if needs_layer {
+            ex_style |= WS_EX_LAYERED.0;
+        }
+        if modifiers.click_through {
+            ex_style |= WS_EX_TRANSPARENT.0;
+        }
+
+        let keep_on_top = modifiers.always_on_top || (original.ex_style & WS_EX_TOPMOST.0) != 0;
+
+        unsafe {
+            SetWindowLongW(handle, GWL_EXSTYLE, ex_style as i32);
+
+            if needs_layer {
+                let alpha = if modifiers.opacity < 255 {
+                    modifiers.opacity
+                } else {
+                    original.layered.map_or(255, |(_, alpha, _)| alpha)
+                };
+                SetLayeredWindowAttributes(handle, COLORREF(0), alpha, LWA_ALPHA)?;
+            } else if let Some((key, alpha, flags)) = original.layered {
+                SetLayeredWindowAttributes(handle, key, alpha, flags)?;
+            }
+
+            SetWindowPos(
+                handle,
+                Some(if keep_on_top { HWND_TOPMOST } else { HWND_NOTOPMOST }),
+                0,
+                0,
+                0,
+                0,
+                SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+            )?;
+        }
+
+        if modifiers.is_default() {
+            state.original = None;
+            if state.frame.is_none() {
+                managed.remove(&hwnd);
+            }
+        }
+        drop(managed);
+
+        self.mark_modifiers(hwnd, read_modifiers(handle, ex_style));
+
+        Ok(())
+    }
+
+    pub fn target_rect(
+        &self,
+        hwnd: isize,
+        resize_to_screen: bool,
+        selected_display: Option<&DisplayInfo>,
+        mode: FrameMode,
+    ) -> anyhow::Result<RECT>
+    {
+        if resize_to_screen && !self.is_borderless(hwnd) {
+            let handle = HWND(hwnd as *mut std::ffi::c_void);
+            let style = mode.apply(unsafe { GetWindowLongW(handle, GWL_STYLE) } as u32);
+            let size = *self.window_size.lock().unwrap();
+            let (x, y, width, height) = placement(handle, style, selected_display, size)?;
+            return Ok(RECT { left: x, top: y, right: x + width, bottom: y + height });
+        }
+
+        window_rect(hwnd)
+    }
+
+    pub fn restore_all(&self) -> anyhow::Result<()>
+    {
+        let _tracked = self.track("Restore all");
+        let managed: Vec<(isize, ManagedWindow)> = self
+            .managed_windows
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(hwnd, state)| (*hwnd, *state))
+            .collect();
+        let mut result = Ok(());
+
+        for (hwnd, state) in managed {
+            let handle = HWND(hwnd as *mut std::ffi::c_void);
+            if !unsafe { IsWindow(Some(handle)) }.as_bool() {
+                continue;
+            }
+
+            if state.original.is_some()
+                && let Err(e) = self.set_modifiers(hwnd, WindowModifiers::default())
+            {
+                result = Err(e);
+            }
+
+            if state.frame.is_some()
+                && self.is_borderless(hwnd)
+                && let Err(e) = self.restore_borders(hwnd)
+            {
+                result = Err(e);
+            }
+        }
+
+        self.managed_windows.lock().unwrap().clear();
+
+        result
+    }
+
+    pub fn move_to_next_display(&self, hwnd: isize) -> anyhow::Result<()>
+    {
+        let displays = self.get_displays();
+        if displays.len() < 2 {
+            return Ok(());
+        }
+
+        let current = display_index(window_rect(hwnd)?, &displays);
+        let next = (current + 1) % displays.len();
+
+        self.move_to_display(hwnd, &displays[current], &displays[next])
+    }
+
+    pub fn send_to_display(&self, hwnd: isize, index: usize) -> anyhow::Result<()>
+    {
+        let displays = self.get_displays();
+        let target = displays
+            .get(index)
+            .ok_or_else(|| anyhow::anyhow!("Display {} is not connected", index + 1))?;
+        let current = display_index(window_rect(hwnd)?, &displays);
+
+        if current == index {
+            return Ok(());
+        }
+
+        self.move_to_display(hwnd, &displays[current], target)
+    }
+
+    pub fn swap_displays(&self, hwnd: isize, other: Option<isize>)
+    -> anyhow::Result<Option<isize>>
+    {
+        let _tracked = self.track("Swap displays");
+        let displays = self.get_displays();
+        if displays.len() < 2 {
+            return Ok(None);
+        }
+
+        let current = display_index(window_rect(hwnd)?, &displays);
+        let other = match other {
+            Some(other) => Some((other, display_index(window_rect(other)?, &displays))),
+            None => {
+                let next = (current + 1) % displays.len();
+                self.get_windows()
+                    .iter()
+                    .filter(|window| window.hwnd != hwnd && self.is_borderless(window.hwnd))
+                    .filter_map(|window| {
+                        window_rect(window.hwnd).ok().map(|rect| (window.hwnd, rect))
+                    })
+                    .find(|(_, rect)| display_index(*rect, &displays) == next)
+                    .map(|(other, _)| (other, next))
+            },
+        };
+
+        let Some((other, other_display)) = other else {
+            self.move_to_display(
+                hwnd,
+                &displays[current],
+                &displays[(current + 1) % displays.len()],
+            )?;
+            return Ok(None);
+        };
+
+        if other_display != current {
+            self.move_to_display(hwnd, &displays[current], &displays[other_display])?;
+            self.move_to_display(other, &displays[other_display], &displays[current])?;
+        }
+
+        Ok(Some(other))
+    }
+
+    pub fn move_to_display(
+        &self,
+        hwnd: isize,
+        from: &DisplayInfo,
+        to: &DisplayInfo,
+    ) -> anyhow::Result<()>
+    {
+        let _tracked = self.track(&format!("Move to {}", to.name));
+        let handle = HWND(hwnd as *mut std::ffi::c_void);
+        let rect = window_rect(hwnd)?;
+        self.touch(hwnd);
+        let width = rect.right - rect.left;
+        let height = rect.bottom - rect.top;
+
+        let fills_display = rect.left == from.x
+            && rect.top == from.y
+            && width == from.width
+            && height == from.height;
+        let frame = self.frame(hwnd);
+        let borderless = self.is_borderless(hwnd);
+        let size = frame.and_then(|frame| frame.size).filter(|_| borderless);
+        let fill = borderless && (fills_display || frame.is_some_and(|frame| frame.fill));
+
+        let (x, y, width, height) = if let Some(size) = size {
+            // Re-anchor the requested client size on the target display rather than rescaling
+            // the outer rect, so the anchor and the frame adjustment survive the move.
+            let style = unsafe { GetWindowLongW(handle, GWL_STYLE) } as u32;
+            placement(handle, style, Some(to), Some(size))?
+        } else if fill {
+            (to.x, to.y, to.width, to.height)
+        } else {
+            let width = width.min(to.width);
+            let height = height.min(to.height);
+            let center_x = (rect.left + rect.right) / 2 - from.x;
+            let center_y = (rect.top + rect.bottom) / 2 - from.y;
+            let x = to.x + rescale(center_x, from.width, to.width) - width / 2;
+            let y = to.y + rescale(center_y, from.height, to.height) - height / 2;
+            (
+                x.clamp(to.x, to.x + to.width - width),
+                y.clamp(to.y, to.y + to.height - height),
+                width,
+                height,
+            )
+        };
+
+        unsafe {
+            SetWindowPos(handle, Some(HWND_TOP), x, y, width, height, SWP_NOZORDER)?;
+        }
+
+        logging::info(
+            "toggle",
+            format!(
+                "Moved to {} {} rect=({}, {}, {}x{}) fill={} size={}",
+                to.name,
+                self.describe(hwnd),
+                x,
+                y,
+                width,
+                height,
+                fill,
+                size.map_or_else(|| "-".to_string(), |size| size.label())
+            ),
+        );
+
+        Ok(())
+    }
+
+    pub fn apply_profile(
+        &self,
+        hwnd: isize,
+        profile: &Profile,
+        displays: &[DisplayInfo],
+    ) -> anyhow::Result<()>
+    {
+        let _tracked = self.track(&format!("Apply profile '{}'", profile.name));
+        let selected_display =
+            if profile.resize_to_screen { profile.resolve_display(displays) } else { None };
+
+        self.set_borderless_sized(
+            hwnd,
+            profile.resize_to_screen,
+            selected_display,
+            profile.frame_mode,
+            profile.size,
+            profile.effects(),
+        )?;
+
+        if !profile.modifiers.is_default() {
+            self.set_modifiers(hwnd, profile.modifiers)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn apply_profile_to_matching(
+        &self,
+        profile: &Profile,
+        displays: &[DisplayInfo],
+    ) -> anyhow::Result<Vec<isize>>
+    {
+        let _tracked = self.track(&format!("Apply profile '{}'", profile.name));
+        let targets: Vec<isize> = self
+            .get_windows()
+            .iter()
+            .filter(|window| !window.is_borderless && profile.matches(window))
+            .map(|window| window.hwnd)
+            .collect();
+
+        logging::info(
+            "automation",
+            format!(
+                "Profile {:?} matched {} window(s) {:X?}",
+                profile.name,
+                targets.len(),
+                targets
+            ),
+        );
+
+        for hwnd in &targets {
+            self.apply_profile(*hwnd, profile, displays)?;
+        }
+
+        Ok(targets)
+    }
+
+    pub fn history(&self) -> (Vec<HistoryEntry>, Vec<HistoryEntry>)
+    {
+        let history = self.history.lock().unwrap();
+        (history.undo_entries().to_vec(), history.redo_entries().to_vec())
+    }
+
+    pub fn undo(&self) -> anyhow::Result<Option<String>>
+    {
+        self.replay(false)
+    }
+
+    pub fn redo(&self) -> anyhow::Result<Option<String>>
+    {
+        self.replay(true)
+    }
+
+    fn replay(&self, redo: bool) -> anyhow::Result<Option<String>>
+    {
+        loop {
+            let entry = {
+                let mut history = self.history.lock().unwrap();
+                if redo { history.pop_redo() } else { history.pop_undo() }
+            };
+            let Some(entry) = entry else {
+                return Ok(None);
+            };
+
+            let changes: Vec<&HistoryChange> = entry
+                .changes
+                .iter()
+                .filter(|change| {
+                    unsafe { IsWindow(Some(HWND(change.hwnd as *mut std::ffi::c_void))) }.as_bool()
+                })
+                .collect();
+            if changes.is_empty() {
+                continue;
+            }
+
+            history::begin(false);
+            let result = changes.iter().try_for_each(|change| {
+                self.apply_state(change.hwnd, if redo { change.after } else { change.before })
+            });
+            history::end();
+
+            let label = entry.label.clone();
+            let mut history = self.history.lock().unwrap();
+            if redo {
+                history.push_undo(entry);
+            } else {
+                history.push_redo(entry);
+            }
+
+            return result.map(|()| Some(label));
+        }
+    }
+
+    fn apply_state(&self, hwnd: isize, state: WindowState) -> anyhow::Result<()>
+    {
+        // Replay the effects recorded with the entry, not the current settings, so undo brings
+        // back the taskbar, cursor and backdrop behaviour the window actually had.
+        let current = self.frame(hwnd).map(|frame| (frame.mode, frame.effects));
+        match (current, state.frame) {
+            (Some(_), None) => self.restore_borders(hwnd)?,
+            (current, Some(mode)) if current != Some((mode, state.effects)) => {
+                BorderlessEffects::stop(hwnd);
+                self.set_borderless_sized(hwnd, false, None, mode, None, state.effects)?
+            },
+            _ => {},
+        }
+
+        if self.modifiers(hwnd) != state.modifiers {
+            self.set_modifiers(hwnd, state.modifiers)?;
+        }
+
+        let rect = state.rect;
+        unsafe {
+            SetWindowPos(
+                HWND(hwnd as *mut std::ffi::c_void),
+                None,
+                rect.left,
+                rect.top,
+                rect.right - rect.left,
+                rect.bottom - rect.top,
+                SWP_FRAMECHANGED | SWP_NOZORDER | SWP_NOACTIVATE,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn track(&self, label: &str) -> Tracked<'_>
+    {
+        history::begin(true);
+        Tracked { manager: self, label: label.to_string() }
+    }
+
+    fn touch(&self, hwnd: isize)
+    {
+        history::touch(hwnd, || self.window_state(hwnd));
+    }
+
+    fn window_state(&self, hwnd: isize) -> Option<WindowState>
+    {
+        let rect = window_rect(hwnd).ok()?;
+        let frame = self.frame(hwnd);
+        Some(WindowState {
+            frame: frame.map(|frame| frame.mode),
+            effects: frame.map(|frame| frame.effects).unwrap_or_default(),
+            rect,
+            modifiers: self.modifiers(hwnd),
+        })
+    }
+
+    fn record(&self, label: &str, before: Vec<(isize, WindowState)>)
+    {
+        let windows = self.get_windows();
+        let changes: Vec<HistoryChange> = before
+            .into_iter()
+            .filter_map(|(hwnd, before)| {
+                let after = self.window_state(hwnd).filter(|after| *after != before)?;
+                let title = windows
+                    .iter()
+                    .find(|window| window.hwnd == hwnd)
+                    .map_or_else(|| format!("0x{:X}", hwnd), |window| window.title.clone());
+                Some(HistoryChange { hwnd, title, before, after })
+            })
+            .collect();
+
+        if !changes.is_empty() {
+            self.history.lock().unwrap().push(HistoryEntry { label: label.to_string(), changes });
+        }
+    }
+
+    fn describe(&self, hwnd: isize) -> String
+    {
+        let title = self
+            .windows
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|window| window.hwnd == hwnd)
+            .map_or_else(|| "?".to_string(), |window| logging::title(&window.title));
+
+        format!("hwnd=0x{:X} title={}", hwnd, title)
+    }
+
+    fn log_result(&self, action: &str, hwnd: isize, details: &str, result: &anyhow::Result<()>)
+    {
+        match result {
+            Ok(()) => {
+                logging::info("toggle", format!("{} {} {}", action, self.describe(hwnd), details))
+            },
+            Err(e) => logging::warn(
+                "toggle",
+                format!(
+                    "{} failed {} {} error={:?}",
+                    action,
+                    self.describe(hwnd),
+                    details,
+                    e.to_string()
+                ),
+            ),
+        }
+    }
+
+    pub fn is_refresh_in_progress(&self) -> bool
+    {
+        *self.refresh_in_progress.lock().unwrap()
+    }
+}
+
+pub fn find_display<'a>(displays: &'a [DisplayInfo], name: Option<&str>)
+-> Option<&'a DisplayInfo>
+{
+    let named = match name {
+        Some(name) if name.eq_ignore_ascii_case("largest") => {
+            displays.iter().max_by_key(|display| display.width as i64 * display.height as i64)
+        },
+        Some(name) => displays.iter().find(|display| display.name.eq_ignore_ascii_case(name)),
+        None => None,
+    };
+
+    named
+        .or_else(|| displays.iter().find(|display| display.is_primary))
+        .or_else(|| displays.first())
+}
+
+pub fn foreground_window() -> Option<isize>
+{
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.0.is_null() {
+            return None;
+        }
+
+        let mut process_id = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut process_id));
+
+        if process_id == GetCurrentProcessId() { None } else { Some(hwnd.0 as isize) }
+    }
+}
+
+pub fn window_at(x: i32, y: i32) -> Option<isize>
+{
+    let mut search = (POINT { x, y }, None::<isize>);
+
+    unsafe {
+        let _ = EnumWindows(
+            Some(window_at_proc),
+            LPARAM(&mut search as *mut (POINT, Option<isize>) as isize),
+        );
+    }
+
+    search.1
+}
+
+pub fn frame_rect(hwnd: isize) -> Option<RECT>
+{
+    let handle = HWND(hwnd as *mut std::ffi::c_void);
+    let mut rect = RECT::default();
+
+    unsafe {
+        let bounds = DwmGetWindowAttribute(
+            handle,
+            DWMWA_EXTENDED_FRAME_BOUNDS,
+            &mut rect as *mut RECT as *mut std::ffi::c_void,
+            std::mem::size_of::<RECT>() as u32,
+        );
+
+        if bounds.is_err() && GetWindowRect(handle, &mut rect).is_err() {
+            return None;
+        }
+    }
+
+    Some(rect)
+}
+
+fn is_cloaked(hwnd: HWND) -> bool
+{
+    let mut cloaked = 0u32;
+
+    unsafe {
+        DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_CLOAKED,
+            &mut cloaked as *mut u32 as *mut std::ffi::c_void,
+            std::mem::size_of::<u32>() as u32,
+        )
+        .is_ok()
+            && cloaked != 0
+    }
+}
+
+fn monitor_name(hwnd: HWND, displays: &[DisplayInfo]) -> Option<String>
+{
+    let mut monitor_info =
+        MONITORINFO { cbSize: std::mem::size_of::<MONITORINFO>() as u32, ..Default::default() };
+
+    unsafe {
+        let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+        if !GetMonitorInfoW(monitor, &mut monitor_info).as_bool() {
+            return None;
+        }
+    }
+
+    displays
+        .iter()
+        .find(|display| {
+            display.x == monitor_info.rcMonitor.left && display.y == monitor_info.rcMonitor.top
+        })
+        .map(|display| display.name.clone())
+}
+
+pub fn style_flags(style: u32) -> Vec<String>
+{
+    decode_flags(
+        style,
+        &[
+            (WS_POPUP.0, "WS_POPUP"),
+            (WS_CHILD.0, "WS_CHILD"),
+            (WS_MINIMIZE.0, "WS_MINIMIZE"),
+            (WS_VISIBLE.0, "WS_VISIBLE"),
+            (WS_DISABLED.0, "WS_DISABLED"),
+            (WS_CLIPSIBLINGS.0, "WS_CLIPSIBLINGS"),
+            (WS_CLIPCHILDREN.0, "WS_CLIPCHILDREN"),
+            (WS_MAXIMIZE.0, "WS_MAXIMIZE"),
+            (WS_CAPTION.0, "WS_CAPTION"),
+            (WS_BORDER.0, "WS_BORDER"),
+            (WS_DLGFRAME.0, "WS_DLGFRAME"),
+            (WS_VSCROLL.0, "WS_VSCROLL"),
+            (WS_HSCROLL.0, "WS_HSCROLL"),
+            (WS_SYSMENU.0, "WS_SYSMENU"),
+            (WS_THICKFRAME.0, "WS_THICKFRAME"),
+            (WS_MINIMIZEBOX.0, "WS_MINIMIZEBOX"),
+            (WS_MAXIMIZEBOX.0, "WS_MAXIMIZEBOX"),
+        ],
+    )
+}
+
+pub fn ex_style_flags(ex_style: u32) -> Vec<String>
+{
+    decode_flags(
+        ex_style,
+        &[
+            (WS_EX_DLGMODALFRAME.0, "WS_EX_DLGMODALFRAME"),
+            (WS_EX_NOPARENTNOTIFY.0, "WS_EX_NOPARENTNOTIFY"),
+            (WS_EX_TOPMOST.0, "WS_EX_TOPMOST"),
+            (WS_EX_ACCEPTFILES.0, "WS_EX_ACCEPTFILES"),
+            (WS_EX_TRANSPARENT.0, "WS_EX_TRANSPARENT"),
+            (WS_EX_MDICHILD.0, "WS_EX_MDICHILD"),
+            (WS_EX_TOOLWINDOW.0, "WS_EX_TOOLWINDOW"),
+            (WS_EX_WINDOWEDGE.0, "WS_EX_WINDOWEDGE"),
+            (WS_EX_CLIENTEDGE.0, "WS_EX_CLIENTEDGE"),
+            (WS_EX_CONTEXTHELP.0, "WS_EX_CONTEXTHELP"),
+            (WS_EX_RIGHT.0, "WS_EX_RIGHT"),
+            (WS_EX_RTLREADING.0, "WS_EX_RTLREADING"),
+            (WS_EX_LEFTSCROLLBAR.0, "WS_EX_LEFTSCROLLBAR"),
+            (WS_EX_CONTROLPARENT.0, "WS_EX_CONTROLPARENT"),
+            (WS_EX_STATICEDGE.0, "WS_EX_STATICEDGE"),
+            (WS_EX_APPWINDOW.0, "WS_EX_APPWINDOW"),
+            (WS_EX_LAYERED.0, "WS_EX_LAYERED"),
+            (WS_EX_NOINHERITLAYOUT.0, "WS_EX_NOINHERITLAYOUT"),
+            (WS_EX_NOREDIRECTIONBITMAP.0, "WS_EX_NOREDIRECTIONBITMAP"),
+            (WS_EX_LAYOUTRTL.0, "WS_EX_LAYOUTRTL"),
+            (WS_EX_COMPOSITED.0, "WS_EX_COMPOSITED"),
+            (WS_EX_NOACTIVATE.0, "WS_EX_NOACTIVATE"),
+        ],
+    )
+}
+
+fn decode_flags(bits: u32, names: &[(u32, &str)]) -> Vec<String>
+{
+    let mut remaining = bits;
+    let mut flags = Vec::new();
+
+    for (flag, name) in names {
+        if remaining & flag == *flag {
+            flags.push(name.to_string());
+            remaining &= !flag;
+        }
+    }
+
+    if remaining != 0 {
+        flags.push(format!("0x{:08X}", remaining));
+    }
+
+    flags
 }
 
-#[derive(Debug, Clone)]
-pub struct DisplayInfo
+fn screen_rect(selected_display: Option<&DisplayInfo>) -> (i32, i32, i32, i32)
 {
-    pub name: String,
-    pub x: i32,
-    pub y: i32,
-    pub width: i32,
-    pub height: i32,
-    pub is_primary: bool,
+    if let Some(display) = selected_display {
+        (display.x, display.y, display.width, display.height)
+    } else {
+        let screen_width = unsafe { GetSystemMetrics(SM_CXSCREEN) };
+        let screen_height = unsafe { GetSystemMetrics(SM_CYSCREEN) };
+        (0, 0, screen_width, screen_height)
+    }
 }
 
-impl WindowInfo
+fn verify_style(hwnd: isize, style: u32) -> anyhow::Result<()>
 {
-    pub fn display_text(&self) -> String
-    {
-        let max_title_len = 30;
-        let max_process_len = 15;
+    let handle = HWND(hwnd as *mut std::ffi::c_void);
+    let actual = unsafe { GetWindowLongW(handle, GWL_STYLE) } as u32;
 
-        let truncated_title = if self.title.chars().count() > max_title_len {
-            let truncated: String = self.title.chars().take(max_title_len - 3).collect();
-            format!("{}...", truncated)
-        } else {
-            self.title.clone()
-        };
+    if (actual & BORDER_STYLES) == (style & BORDER_STYLES) {
+        return Ok(());
+    }
 
-        let truncated_process = if self.process_name.chars().count() > max_process_len {
-            let truncated: String = self.process_name.chars().take(max_process_len - 3).collect();
-            format!("{}...", truncated)
-        } else {
-            self.process_name.clone()
-        };
+    Err(explain_failure(hwnd, anyhow::anyhow!("the window kept its frame")))
+}
 
-        format!("{} ({})", truncated_title, truncated_process)
+fn verify_rect(hwnd: isize, expected: RECT) -> anyhow::Result<()>
+{
+    let actual = window_rect(hwnd)?;
+
+    if placed_within_tolerance(actual, expected) {
+        return Ok(());
     }
+
+    anyhow::bail!(
+        "The frame was removed, but the window sized itself to {}x{} at ({}, {}) instead of {}x{} at ({}, {})",
+        actual.right - actual.left,
+        actual.bottom - actual.top,
+        actual.left,
+        actual.top,
+        expected.right - expected.left,
+        expected.bottom - expected.top,
+        expected.left,
+        expected.top
+    )
 }
 
-impl DisplayInfo
+fn placed_within_tolerance(actual: RECT, expected: RECT) -> bool
 {
-    pub fn display_text(&self) -> String
-    {
-        let primary_indicator = if self.is_primary { " (Primary)" } else { "" };
-        format!("{} - {}x{}{}", self.name, self.width, self.height, primary_indicator)
+    [
+        actual.left - expected.left,
+        actual.top - expected.top,
+        actual.right - expected.right,
+        actual.bottom - expected.bottom,
+    ]
+    .iter()
+    .all(|offset| offset.abs() <= PLACEMENT_TOLERANCE)
+}
+
+fn explain_failure(hwnd: isize, error: anyhow::Error) -> anyhow::Error
+{
+    let mut process_id = 0u32;
+    unsafe {
+        GetWindowThreadProcessId(HWND(hwnd as *mut std::ffi::c_void), Some(&mut process_id));
+    }
+
+    match process::elevation(process_id) {
+        Elevation::Elevated if !process::is_elevated() => anyhow::anyhow!(
+            "Access denied — the target runs elevated, restart ihateborders as admin"
+        ),
+        Elevation::Unknown => anyhow::anyhow!(
+            "Access denied — the target is a protected process or runs as another user"
+        ),
+        _ => anyhow::anyhow!("The window ignored the change ({})", error),
     }
 }
 
-pub struct WindowManager
+fn placement(
+    hwnd: HWND,
+    style: u32,
+    selected_display: Option<&DisplayInfo>,
+    size: Option<WindowSize>,
+) -> anyhow::Result<(i32, i32, i32, i32)>
 {
-    windows: Vec<WindowInfo>,
-    refresh_in_progress: Arc<Mutex<bool>>,
+    let screen = screen_rect(selected_display);
+
+    match size {
+        Some(size) => outer_size(hwnd, style, size).map(|outer| size.place(screen, outer)),
+        None => Ok(screen),
+    }
 }
 
-impl WindowManager
+fn outer_size(hwnd: HWND, style: u32, size: WindowSize) -> anyhow::Result<(i32, i32)>
 {
-    pub fn new() -> Self
-    {
-        Self { windows: Vec::new(), refresh_in_progress: Arc::new(Mutex::new(false)) }
+    let mut rect = RECT { left: 0, top: 0, right: size.width, bottom: size.height };
+
+    unsafe {
+        let ex_style = GetWindowLongW(hwnd, GWL_EXSTYLE) as u32;
+        let menu = !GetMenu(hwnd).is_invalid();
+        AdjustWindowRectEx(&mut rect, WINDOW_STYLE(style), menu, WINDOW_EX_STYLE(ex_style))?;
     }
 
-    pub fn refresh_windows_async(&self) -> std::sync::mpsc::Receiver<Vec<WindowInfo>>
-    {
-        let (sender, receiver) = std::sync::mpsc::channel();
-        let refresh_flag = Arc::clone(&self.refresh_in_progress);
+    Ok((rect.right - rect.left, rect.bottom - rect.top))
+}
 
-        std::thread::spawn(move || {
-            {
-                let mut in_progress = refresh_flag.lock().unwrap();
-                if *in_progress {
-                    let _ = sender.send(Vec::new());
-                    return;
-                }
-                *in_progress = true;
-            }
+// Compares runs of digits by value, so "Display 10" sorts after "Display 2".
+fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering
+{
+    let (mut a, mut b) = (a.chars().peekable(), b.chars().peekable());
+    let number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+        let mut value = 0u64;
+        while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+            value = value.saturating_mul(10).saturating_add(digit as u64 - '0' as u64);
+        }
+        value
+    };
+
+    loop {
+        let ordering = match (a.peek(), b.peek()) {
+            (None, None) => return std::cmp::Ordering::Equal,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                number(&mut a).cmp(&number(&mut b))
+            },
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                a.next();
+                b.next();
+                ordering
+            },
+            (x, y) => x.is_some().cmp(&y.is_some()),
+        };
 
-            let mut windows = Vec::new();
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+}
 
-            unsafe {
-                if EnumWindows(
-                    Some(enum_windows_proc),
-                    LPARAM(&mut windows as *mut Vec<WindowInfo> as isize),
-                )
-                .is_ok()
-                {
-                    windows.sort_by(|a: &WindowInfo, b: &WindowInfo| a.title.cmp(&b.title));
-                }
-            }
+fn display_index(rect: RECT, displays: &[DisplayInfo]) -> usize
+{
+    let center_x = (rect.left + rect.right) / 2;
+    let center_y = (rect.top + rect.bottom) / 2;
+
+    displays
+        .iter()
+        .position(|display| {
+            center_x >= display.x
+                && center_x < display.x + display.width
+                && center_y >= display.y
+                && center_y < display.y + display.height
+        })
+        .unwrap_or(0)
+}
 
-            *refresh_flag.lock().unwrap() = false;
+fn rescale(offset: i32, from: i32, to: i32) -> i32
+{
+    (offset as i64 * to as i64 / from.max(1) as i64) as i32
+}
 
-            let _ = sender.send(windows);
-        });
+fn window_rect(hwnd: isize) -> anyhow::Result<RECT>
+{
+    let handle = HWND(hwnd as *mut std::ffi::c_void);
+    let mut rect = RECT::default();
 
-        receiver
+    unsafe {
+        if !IsWindow(Some(handle)).as_bool() {
+            anyhow::bail!("Window no longer exists");
+        }
+
+        GetWindowRect(handle, &mut rect)?;
     }
 
-    pub fn get_windows(&self) -> &[WindowInfo]
-    {
-        &self.windows
+    Ok(rect)
+}
+
+fn enumerate_displays() -> Vec<DisplayInfo>
+{
+    let mut displays = Vec::new();
+
+    unsafe {
+        let _ = EnumDisplayMonitors(
+            Some(HDC::default()),
+            None,
+            Some(enum_monitors_proc),
+            LPARAM(&mut displays as *mut Vec<DisplayInfo> as isize),
+        );
     }
 
-    pub fn set_windows(&mut self, windows: Vec<WindowInfo>)
-    {
-        self.windows = windows;
+    displays.sort_by(|a: &DisplayInfo, b: &DisplayInfo| {
+        if a.is_primary && !b.is_primary {
+            std::cmp::Ordering::Less
+        } else if !a.is_primary && b.is_primary {
+            std::cmp::Ordering::Greater
+        } else {
+            a.name.cmp(&b.name)
+        }
+    });
+
+    let mut known = KNOWN_DISPLAYS.lock().unwrap();
+    if *known != displays {
+        let layout: Vec<String> = displays
+            .iter()
+            .map(|display| {
+                format!(
+                    "{} {}x{} at ({}, {}){}",
+                    display.name,
+                    display.width,
+                    display.height,
+                    display.x,
+                    display.y,
+                    if display.is_primary { " primary" } else { "" }
+                )
+            })
+            .collect();
+        logging::info(
+            "display",
+            format!("Detected {} display(s): {}", displays.len(), layout.join(", ")),
+        );
+        *known = displays.clone();
     }
 
-    pub fn get_displays(&self) -> Vec<DisplayInfo>
-    {
-        let mut displays = Vec::new();
+    displays
+}
 
-        unsafe {
-            let _ = EnumDisplayMonitors(
-                Some(HDC::default()),
-                None,
-                Some(enum_monitors_proc),
-                LPARAM(&mut displays as *mut Vec<DisplayInfo> as isize),
-            );
+fn enumerate_windows(filters: WindowFilters) -> Vec<WindowInfo>
+{
+    let started = std::time::Instant::now();
+    let mut context = EnumContext { windows: Vec::new(), filters };
+    process::forget_exited(&process::list_processes());
+
+    unsafe {
+        if EnumWindows(Some(enum_windows_proc), LPARAM(&mut context as *mut EnumContext as isize))
+            .is_ok()
+        {
+            context.windows.sort_by(|a: &WindowInfo, b: &WindowInfo| a.title.cmp(&b.title));
         }
+    }
 
-        displays.sort_by(|a: &DisplayInfo, b: &DisplayInfo| {
-            if a.is_primary && !b.is_primary {
-                std::cmp::Ordering::Less
-            } else if !a.is_primary && b.is_primary {
-                std::cmp::Ordering::Greater
-            } else {
-                a.name.cmp(&b.name)
-            }
-        });
+    let mut windows = context.windows;
 
-        displays
+    let displays = enumerate_displays();
+    for window in &mut windows {
+        window.monitor = monitor_name(HWND(window.hwnd as *mut std::ffi::c_void), &displays);
     }
 
-    pub fn toggle_borderless(
-        &self,
-        hwnd: isize,
-        resize_to_screen: bool,
-        selected_display: Option<&DisplayInfo>,
-    ) -> anyhow::Result<()>
-    {
-        let hwnd = HWND(hwnd as *mut std::ffi::c_void);
+    logging::debug(
+        "enumeration",
+        format!("Enumerated {} windows in {} ms", windows.len(), started.elapsed().as_millis()),
+    );
 
-        unsafe {
-            let current_style = GetWindowLongW(hwnd, GWL_STYLE) as u32;
+    windows
+}
 
-            let border_styles = WS_BORDER.0 | WS_CAPTION.0 | WS_THICKFRAME.0 | WS_DLGFRAME.0;
+fn full_borderless_style(style: u32) -> u32
+{
+    style & !BORDER_STYLES
+}
 
-            let new_style = if (current_style & border_styles) != 0 {
-                current_style & !border_styles
-            } else {
-                current_style | WS_CAPTION.0 | WS_THICKFRAME.0
-            };
+fn caption_removed_style(style: u32) -> u32
+{
+    (style & !WS_CAPTION.0) | WS_THICKFRAME.0
+}
 
-            SetWindowLongW(hwnd, GWL_STYLE, new_style as i32);
+fn frame_removed_style(style: u32) -> u32
+{
+    style & !WS_THICKFRAME.0
+}
 
-            if resize_to_screen && (current_style & border_styles) != 0 {
-                let (x, y, width, height) = if let Some(display) = selected_display {
-                    (display.x, display.y, display.width, display.height)
-                } else {
-                    let screen_width = GetSystemMetrics(SM_CXSCREEN);
-                    let screen_height = GetSystemMetrics(SM_CYSCREEN);
-                    (0, 0, screen_width, screen_height)
-                };
+fn thin_border_style(style: u32) -> u32
+{
+    (style & !BORDER_STYLES) | WS_BORDER.0
+}
 
-                SetWindowPos(
-                    hwnd,
-                    Some(HWND_TOP),
-                    x,
-                    y,
-                    width,
-                    height,
-                    SWP_FRAMECHANGED | SWP_NOZORDER,
-                )?;
-            } else {
-                SetWindowPos(
-                    hwnd,
-                    Some(HWND_TOP),
-                    0,
-                    0,
-                    0,
-                    0,
-                    SWP_FRAMECHANGED | SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER,
-                )?;
-            }
+fn restored_style(original: u32, current: u32) -> u32
+{
+    (current & !BORDER_STYLES) | (original & BORDER_STYLES)
+}
+
+fn read_modifiers(hwnd: HWND, ex_style: u32) -> WindowModifiers
+{
+    let mut opacity = 255u8;
+
+    if (ex_style & WS_EX_LAYERED.0) != 0 {
+        let mut alpha = 255u8;
+        let mut flags = LAYERED_WINDOW_ATTRIBUTES_FLAGS(0);
+        let read =
+            unsafe { GetLayeredWindowAttributes(hwnd, None, Some(&mut alpha), Some(&mut flags)) };
+
+        if read.is_ok() && (flags.0 & LWA_ALPHA.0) != 0 {
+            opacity = alpha;
         }
+    }
 
-        Ok(())
+    let click_through = WS_EX_LAYERED.0 | WS_EX_TRANSPARENT.0;
+
+    WindowModifiers {
+        always_on_top: (ex_style & WS_EX_TOPMOST.0) != 0,
+        opacity,
+        click_through: (ex_style & click_through) == click_through,
     }
+}
 
-    pub fn is_refresh_in_progress(&self) -> bool
-    {
-        *self.refresh_in_progress.lock().unwrap()
+fn refresh_frame(hwnd: HWND) -> windows::core::Result<()>
+{
+    unsafe {
+        SetWindowPos(
+            hwnd,
+            Some(HWND_TOP),
+            0,
+            0,
+            0,
+            0,
+            SWP_FRAMECHANGED | SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER,
+        )
+    }
+}
+
+unsafe extern "system" fn window_at_proc(hwnd: HWND, lparam: LPARAM) -> windows::core::BOOL
+{
+    unsafe {
+        let (point, found) = &mut *(lparam.0 as *mut (POINT, Option<isize>));
+
+        if !IsWindowVisible(hwnd).as_bool() || is_cloaked(hwnd) || GetWindowTextLengthW(hwnd) == 0 {
+            return true.into();
+        }
+
+        if (GetWindowLongW(hwnd, GWL_EXSTYLE) as u32 & WS_EX_TRANSPARENT.0) != 0 {
+            return true.into();
+        }
+
+        let mut process_id = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut process_id));
+        if process_id == GetCurrentProcessId() {
+            return true.into();
+        }
+
+        let Some(rect) = frame_rect(hwnd.0 as isize) else {
+            return true.into();
+        };
+
+        if point.x >= rect.left
+            && point.x < rect.right
+            && point.y >= rect.top
+            && point.y < rect.bottom
+        {
+            *found = Some(hwnd.0 as isize);
+            return false.into();
+        }
+
+        true.into()
     }
 }
 
 unsafe extern "system" fn enum_windows_proc(hwnd: HWND, lparam: LPARAM) -> windows::core::BOOL
 {
     unsafe {
-        let windows = &mut *(lparam.0 as *mut Vec<WindowInfo>);
+        let context = &mut *(lparam.0 as *mut EnumContext);
+        let filters = &context.filters;
 
         if !IsWindowVisible(hwnd).as_bool() {
             return true.into();
@@ -232,39 +1982,88 @@ unsafe extern "system" fn enum_windows_proc(hwnd: HWND, lparam: LPARAM) -> windo
 
         let mut title_buffer = [0u16; 256];
         let title_len = GetWindowTextW(hwnd, &mut title_buffer);
-        if title_len == 0 {
+        let title = String::from_utf16_lossy(&title_buffer[..title_len.max(0) as usize]);
+
+        if (title.trim().is_empty() && !filters.show_untitled_windows) || title == "ihateborders" {
             return true.into();
         }
 
-        let title = String::from_utf16_lossy(&title_buffer[..title_len as usize]);
+        let ex_style = GetWindowLongW(hwnd, GWL_EXSTYLE) as u32;
+        if (ex_style & WS_EX_TOOLWINDOW.0) != 0 && !filters.show_tool_windows {
+            return true.into();
+        }
 
-        if title.trim().is_empty()
-            || title.starts_with("Program Manager")
-            || title == "ihateborders"
-        {
+        let is_owned = GetWindow(hwnd, GW_OWNER).is_ok_and(|owner| !owner.0.is_null());
+        if is_owned && !filters.show_owned_windows {
+            return true.into();
+        }
+
+        let is_cloaked = is_cloaked(hwnd);
+        if is_cloaked && !filters.show_cloaked_windows {
+            return true.into();
+        }
+
+        let is_minimized = IsIconic(hwnd).as_bool();
+        if is_minimized && !filters.show_minimized_windows {
             return true.into();
         }
 
         let mut process_id = 0u32;
-        GetWindowThreadProcessId(hwnd, Some(&mut process_id));
+        let thread_id = GetWindowThreadProcessId(hwnd, Some(&mut process_id));
 
         let process_name = get_process_name(process_id).unwrap_or_else(|| "Unknown".to_string());
 
-        if process_name.to_lowercase() == "ihateborders" {
+        if process_name.to_lowercase() == "ihateborders"
+            || filters.is_excluded(&title, &process_name)
+        {
             return true.into();
         }
 
         let current_style = GetWindowLongW(hwnd, GWL_STYLE) as u32;
-        let border_styles = WS_BORDER.0 | WS_CAPTION.0 | WS_THICKFRAME.0 | WS_DLGFRAME.0;
-        let is_borderless = (current_style & border_styles) == 0;
+        let is_borderless = (current_style & BORDER_STYLES) == 0;
+
+        let mut class_buffer = [0u16; 256];
+        let class_len = GetClassNameW(hwnd, &mut class_buffer);
+        let class_name = String::from_utf16_lossy(&class_buffer[..class_len.max(0) as usize]);
+
+        let mut client_rect = RECT::default();
+        let _ = GetClientRect(hwnd, &mut client_rect);
+        let mut client_origin = POINT::default();
+        let _ = ClientToScreen(hwnd, &mut client_origin);
+
+        let details = process::details(process_id);
+        let version = details.image_path.as_deref().map(process::version_info).unwrap_or_default();
 
         let icon_data = extract_window_icon(hwnd);
 
-        windows.push(WindowInfo {
+        context.windows.push(WindowInfo {
             hwnd: hwnd.0 as isize,
             title,
             process_name,
             is_borderless,
+            modifiers: read_modifiers(hwnd, ex_style),
+            process_id,
+            thread_id,
+            class_name,
+            exe_path: details.image_path,
+            product_name: version.product_name,
+            product_version: version.product_version,
+            elevation: details.elevation,
+            rect: frame_rect(hwnd.0 as isize).map(WindowRect::from_rect).unwrap_or_default(),
+            client_rect: WindowRect {
+                x: client_origin.x,
+                y: client_origin.y,
+                width: client_rect.right,
+                height: client_rect.bottom,
+            },
+            monitor: None,
+            style: current_style,
+            ex_style,
+            is_owned,
+            is_cloaked,
+            is_minimized,
+            is_maximized: IsZoomed(hwnd).as_bool(),
+            z_order: context.windows.len(),
             icon_data,
         });
 
@@ -314,10 +2113,24 @@ unsafe extern "system" fn enum_monitors_proc(
         let displays_ptr = lparam.0 as *mut Vec<DisplayInfo>;
         let displays = &mut *displays_ptr;
 
-        let mut monitor_info =
-            MONITORINFO { cbSize: std::mem::size_of::<MONITORINFO>() as u32, ..Default::default() };
+        let mut monitor_info_ex = MONITORINFOEXW {
+            monitorInfo: MONITORINFO {
+                cbSize: std::mem::size_of::<MONITORINFOEXW>() as u32,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
 
-        if GetMonitorInfoW(hmonitor, &mut monitor_info).as_bool() {
+        if GetMonitorInfoW(
+            hmonitor,
+            &mut monitor_info_ex as *mut MONITORINFOEXW as *mut MONITORINFO,
+        )
+        .as_bool()
+        {
+            let monitor_info = monitor_info_ex.monitorInfo;
+            let device_name = String::from_utf16_lossy(&monitor_info_ex.szDevice)
+                .trim_end_matches('\0')
+                .to_string();
             let width = monitor_info.rcMonitor.right - monitor_info.rcMonitor.left;
             let height = monitor_info.rcMonitor.bottom - monitor_info.rcMonitor.top;
             let is_primary = monitor_info.dwFlags == 1;
@@ -326,6 +2139,7 @@ unsafe extern "system" fn enum_monitors_proc(
 
             displays.push(DisplayInfo {
                 name,
+                device_name,
                 x: monitor_info.rcMonitor.left,
                 y: monitor_info.rcMonitor.top,
                 width,
@@ -485,3 +2299,185 @@ fn extract_window_icon(hwnd: HWND) -> Option<Vec<u8>>
         gdi_resources.get_bitmap_data(size)
     }
 }
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    const OVERLAPPED: u32 = WS_CAPTION.0
+        | WS_SYSMENU.0
+        | WS_THICKFRAME.0
+        | WS_MINIMIZEBOX.0
+        | WS_MAXIMIZEBOX.0
+        | WS_VISIBLE.0
+        | WS_CLIPSIBLINGS.0
+        | WS_CLIPCHILDREN.0;
+    const DIALOG: u32 = WS_POPUP.0 | WS_CAPTION.0 | WS_SYSMENU.0 | WS_VISIBLE.0;
+    const FIXED: u32 = WS_CAPTION.0 | WS_SYSMENU.0 | WS_MINIMIZEBOX.0 | WS_VISIBLE.0;
+    const POPUP: u32 = WS_POPUP.0 | WS_VISIBLE.0;
+
+    const OTHER_BITS: u32 =
+        WS_SYSMENU.0 | WS_MINIMIZEBOX.0 | WS_MAXIMIZEBOX.0 | WS_VISIBLE.0 | WS_CLIPSIBLINGS.0;
+
+    #[test]
+    fn full_borderless_clears_every_border_bit()
+    {
+        let style = FrameMode::Full.apply(OVERLAPPED);
+
+        assert_eq!(style & BORDER_STYLES, 0);
+        assert_eq!(style & OTHER_BITS, OVERLAPPED & OTHER_BITS);
+    }
+
+    #[test]
+    fn caption_only_removes_the_caption_and_keeps_the_sizing_frame()
+    {
+        let style = FrameMode::CaptionOnly.apply(OVERLAPPED);
+
+        assert_eq!(style & WS_CAPTION.0, 0);
+        assert_eq!(style & WS_BORDER.0, 0);
+        assert_eq!(style & WS_DLGFRAME.0, 0);
+        assert_ne!(style & WS_THICKFRAME.0, 0);
+        assert_eq!(style & OTHER_BITS, OVERLAPPED & OTHER_BITS);
+
+        assert_ne!(FrameMode::CaptionOnly.apply(FIXED) & WS_THICKFRAME.0, 0);
+    }
+
+    #[test]
+    fn frame_only_removes_the_sizing_frame_and_keeps_the_caption()
+    {
+        let style = FrameMode::FrameOnly.apply(OVERLAPPED);
+
+        assert_eq!(style & WS_THICKFRAME.0, 0);
+        assert_eq!(style & WS_CAPTION.0, WS_CAPTION.0);
+        assert_eq!(style & OTHER_BITS, OVERLAPPED & OTHER_BITS);
+    }
+
+    #[test]
+    fn thin_border_keeps_only_the_one_pixel_border()
+    {
+        let style = FrameMode::ThinBorder.apply(OVERLAPPED);
+
+        assert_eq!(style & BORDER_STYLES, WS_BORDER.0);
+        assert_eq!(style & OTHER_BITS, OVERLAPPED & OTHER_BITS);
+
+        assert_eq!(FrameMode::ThinBorder.apply(POPUP) & BORDER_STYLES, WS_BORDER.0);
+    }
+
+    #[test]
+    fn restoring_undoes_every_mode()
+    {
+        for original in [OVERLAPPED, DIALOG, FIXED, POPUP] {
+            for mode in FrameMode::ALL {
+                assert_eq!(
+                    restored_style(original, mode.apply(original)),
+                    original,
+                    "{:?} on {:#010X}",
+                    mode,
+                    original
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn restoring_keeps_changes_made_outside_the_border_bits()
+    {
+        let current = FrameMode::Full.apply(OVERLAPPED) & !WS_VISIBLE.0;
+
+        assert_eq!(restored_style(OVERLAPPED, current), OVERLAPPED & !WS_VISIBLE.0);
+    }
+
+    #[test]
+    fn placement_tolerates_windows_that_snap_their_size()
+    {
+        let expected = RECT { left: 0, top: 0, right: 1920, bottom: 1080 };
+
+        assert!(placed_within_tolerance(expected, expected));
+        assert!(placed_within_tolerance(RECT { right: 1916, bottom: 1078, ..expected }, expected));
+        assert!(placed_within_tolerance(
+            RECT { left: -8, top: -8, right: 1928, bottom: 1088 },
+            expected
+        ));
+    }
+
+    #[test]
+    fn placement_rejects_windows_that_keep_their_size()
+    {
+        let expected = RECT { left: 0, top: 0, right: 1920, bottom: 1080 };
+
+        assert!(!placed_within_tolerance(
+            RECT { left: 320, top: 180, right: 1600, bottom: 900 },
+            expected
+        ));
+        assert!(!placed_within_tolerance(RECT { right: 1900, ..expected }, expected));
+        assert!(!placed_within_tolerance(
+            RECT { left: 1920, top: 0, right: 3840, bottom: 1080 },
+            expected
+        ));
+    }
+
+    fn window(title: &str, process_name: &str, monitor: Option<&str>) -> WindowInfo
+    {
+        WindowInfo {
+            title: title.to_string(),
+            process_name: process_name.to_string(),
+            monitor: monitor.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn windows() -> Vec<WindowInfo>
+    {
+        vec![
+            WindowInfo {
+                is_borderless: true,
+                z_order: 2,
+                ..window("notes", "Notepad", Some("Display 10"))
+            },
+            WindowInfo { z_order: 0, ..window("Elden Ring", "eldenring", Some("Display 2")) },
+            WindowInfo { z_order: 3, ..window("Calculator", "calc", None) },
+            WindowInfo {
+                is_borderless: true,
+                z_order: 1,
+                ..window("Brave", "brave", Some("Display 1"))
+            },
+        ]
+    }
+
+    #[test]
+    fn sorts_by_every_key()
+    {
+        let windows = windows();
+
+        assert_eq!(WindowSort::Title.sorted_indices(&windows), [3, 2, 1, 0]);
+        assert_eq!(WindowSort::Process.sorted_indices(&windows), [3, 2, 1, 0]);
+        assert_eq!(WindowSort::State.sorted_indices(&windows), [3, 0, 2, 1]);
+        assert_eq!(WindowSort::Monitor.sorted_indices(&windows), [2, 3, 1, 0]);
+        assert_eq!(WindowSort::RecentlyActive.sorted_indices(&windows), [1, 3, 0, 2]);
+    }
+
+    #[test]
+    fn process_sort_breaks_ties_by_title()
+    {
+        let windows = vec![
+            window("zsh", "WindowsTerminal", None),
+            window("Settings", "SystemSettings", None),
+            window("cmd", "windowsterminal", None),
+        ];
+
+        assert_eq!(WindowSort::Process.sorted_indices(&windows), [1, 2, 0]);
+    }
+
+    #[test]
+    fn compares_numbers_in_names_by_value()
+    {
+        use std::cmp::Ordering;
+
+        assert_eq!(natural_cmp("Display 2", "Display 10"), Ordering::Less);
+        assert_eq!(natural_cmp("Display 10", "Display 9"), Ordering::Greater);
+        assert_eq!(natural_cmp("display 3", "Display 3"), Ordering::Equal);
+        assert_eq!(natural_cmp("Display", "Display 1"), Ordering::Less);
+        assert_eq!(natural_cmp("Display 02", "Display 2"), Ordering::Equal);
+    }
+}